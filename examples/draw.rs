@@ -5,7 +5,11 @@ use crossterm::{event::{self, DisableMouseCapture, EnableMouseCapture, Event, Ke
         LeaveAlternateScreen,
     }};
 use glam::{dvec2, DMat3, DVec2};
-use tactile::{get_tiling_type, IsohedralTiling};
+use tactile::{
+    get_tiling_type,
+    palette::{Builtin, TileColouring},
+    IsohedralTiling,
+};
 use tui::{
     backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Layout},
@@ -22,6 +26,7 @@ struct App {
     tile_type_num: usize,
     tiling: IsohedralTiling,
     edges_shapes: Vec<Vec<DVec2>>,
+    colouring: Box<dyn TileColouring>,
     bound: f64,
 }
 
@@ -35,6 +40,7 @@ impl App {
             tile_type_num,
             tiling,
             edges_shapes: vec![],
+            colouring: Box::new(Builtin::default()),
             bound: 3.0,
         };
         app.set_default_edges();
@@ -61,9 +67,7 @@ impl App {
     pub fn set_default_edges(&mut self) {
         self.edges_shapes.clear();
         for _ in 0..self.tiling.num_edge_shapes() {
-            let mut edge = vec![];
-            edge.push(dvec2(0.0, 0.0));
-            edge.push(dvec2(1.0, 0.0));
+            let edge = vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)];
             self.edges_shapes.push(edge);
         }
     }
@@ -114,10 +118,8 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<(
                 KeyCode::Up => {
                     app.bound += 0.5;
                 }
-                KeyCode::Down => {
-                    if app.bound > 1.0 {
-                        app.bound -= 0.5;
-                    }
+                KeyCode::Down if app.bound > 1.0 => {
+                    app.bound -= 0.5;
                 }
                 KeyCode::Right => {
                     app.next_tile_type();
@@ -132,7 +134,6 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<(
 }
 
 fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
-    let colors = [Color::Blue, Color::Red, Color::Green];
     let chunks = Layout::default()
         .constraints([Constraint::Min(1), Constraint::Length(1)])
         .direction(tui::layout::Direction::Vertical)
@@ -152,8 +153,8 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
                 .tiling
                 .fill_region(-xbound, -ybound, xbound, ybound)
             {
-                let c = app.tiling.colour(tile.t1, tile.t2, tile.aspect);
-                draw_tile(ctx, app, &tile.transform, colors[c as usize]);
+                let c = app.colouring.colour(&app.tiling, &tile);
+                draw_tile(ctx, app, &tile.transform, Color::Rgb(c.r, c.g, c.b));
             }
         })
         .x_bounds([-xbound, xbound])
@@ -18,6 +18,16 @@ fn main() {
     for (i, v) in tiling.vertices().iter().enumerate() {
         println!("    - vertex {}: {}", i, v);
     }
+    for info in tiling.parameters_info() {
+        println!(
+            "    - parameter {} ({}): {} [{:.3}, {:.3}]",
+            info.index,
+            info.name,
+            info.description,
+            info.range.start(),
+            info.range.end()
+        );
+    }
     println!("  - {} distinct edge shapes", tiling.num_edge_shapes());
     println!("  - the edges of the prototile are:");
     for (i, shape) in tiling.shapes().enumerate() {
@@ -0,0 +1,290 @@
+//! Which tiles of a tiling share an edge.
+//!
+//! See [`IsohedralTiling::adjacency`].
+use glam::DMat3;
+
+use crate::{geometry::BoundingBox, IsohedralTiling};
+
+/// The position of a tile: its lattice coordinates and its aspect, as given by
+/// [`fill_region`](IsohedralTiling::fill_region).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TileCoord {
+    /// The number of copies of the first translation vector.
+    pub t1: isize,
+    /// The number of copies of the second translation vector.
+    pub t2: isize,
+    /// The aspect of the tile.
+    pub aspect: usize,
+}
+
+impl TileCoord {
+    /// Creates a new tile coordinate.
+    pub fn new(t1: isize, t2: isize, aspect: usize) -> Self {
+        Self { t1, t2, aspect }
+    }
+}
+
+/// Which tiles count as neighbours of a tile.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighbourhood {
+    /// The tiles sharing an edge with the tile.
+    #[default]
+    Edge,
+    /// The tiles sharing at least a vertex with the tile, which includes the ones sharing an
+    /// edge.
+    Vertex,
+}
+
+/// The neighbours of every tile of a tiling.
+///
+/// Tiling edges are numbered like the vertices of the prototile: edge `idx` goes from vertex
+/// `idx` to vertex `idx + 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adjacency {
+    num_edges: usize,
+    /// For each aspect and edge, the lattice offset and aspect of the neighbour, and the index of
+    /// the shared edge in the neighbour
+    table: Vec<(isize, isize, usize, usize)>,
+    /// For each aspect, the lattice offsets and aspects of the distinct tiles sharing an edge
+    edge_neighbours: Vec<Vec<(isize, isize, usize)>>,
+    /// For each aspect, the lattice offsets and aspects of the tiles sharing a vertex
+    vertex_neighbours: Vec<Vec<(isize, isize, usize)>>,
+}
+
+impl Adjacency {
+    /// The number of edges of each tile.
+    pub fn num_edges(&self) -> usize {
+        self.num_edges
+    }
+
+    /// Returns the tile on the other side of the given edge of `tile`, along with the index of the
+    /// same edge in that tile.
+    pub fn across(&self, tile: TileCoord, edge: usize) -> (TileCoord, usize) {
+        let (dt1, dt2, aspect, other) = self.table[tile.aspect * self.num_edges + edge];
+        (TileCoord::new(tile.t1 + dt1, tile.t2 + dt2, aspect), other)
+    }
+
+    /// Iterates over the neighbours of `tile`, in the order of its edges.
+    ///
+    /// A tile sharing several edges with `tile` is returned once for each edge, see
+    /// [`Adjacency::neighbourhood`] to get each neighbour once.
+    pub fn neighbours(&self, tile: TileCoord) -> impl Iterator<Item = TileCoord> + '_ {
+        (0..self.num_edges).map(move |edge| self.across(tile, edge).0)
+    }
+
+    /// Iterates over the distinct neighbours of `tile` of the given kind.
+    pub fn neighbourhood(
+        &self,
+        tile: TileCoord,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = TileCoord> + '_ {
+        let table = match neighbourhood {
+            Neighbourhood::Edge => &self.edge_neighbours,
+            Neighbourhood::Vertex => &self.vertex_neighbours,
+        };
+        table[tile.aspect]
+            .iter()
+            .map(move |&(dt1, dt2, aspect)| TileCoord::new(tile.t1 + dt1, tile.t2 + dt2, aspect))
+    }
+}
+
+/// How an edge of a tile is paired with another edge of the same tile, its partner, through the
+/// neighbour sharing the edge.
+///
+/// Both edges are copies of each other in the tiling, so changing the shape of one of them changes
+/// the other one too.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgePairing {
+    /// The index of the edge.
+    pub edge: usize,
+    /// The neighbour sharing the edge, relative to the tile: the lattice coordinates are offsets.
+    pub neighbour: TileCoord,
+    /// The index of the shared edge in the neighbour, which is also the index of the partner edge
+    /// in the tile.
+    pub partner: usize,
+    /// The isometry mapping the edge onto its partner. Its inverse maps the tile onto the
+    /// neighbour.
+    pub transform: DMat3,
+    /// Whether the transform maps the start of the edge to the end of its partner.
+    pub reversed: bool,
+}
+
+impl IsohedralTiling {
+    /// Computes how each edge of the tile with the given aspect is paired with another one, in
+    /// the order of the edges.
+    ///
+    /// The transforms apply to the tile placed by [`aspect_transform`], and use the current
+    /// parameters.
+    ///
+    /// [`aspect_transform`]: IsohedralTiling::aspect_transform
+    pub fn edge_pairings(&self, aspect: usize) -> Vec<EdgePairing> {
+        let adjacency = self.adjacency();
+        let tile = TileCoord::new(0, 0, aspect);
+        let m = *self.aspect_transform(aspect);
+        let verts = self.vertices();
+        let n = verts.len();
+
+        (0..n)
+            .map(|edge| {
+                let (neighbour, partner) = adjacency.across(tile, edge);
+                let transform = m * self
+                    .tile_transform(neighbour.t1, neighbour.t2, neighbour.aspect)
+                    .inverse();
+                let start = transform.transform_point2(m.transform_point2(verts[edge]));
+                let end = m.transform_point2(verts[(partner + 1) % n]);
+                let scale = verts[edge].distance(verts[(edge + 1) % n]);
+                EdgePairing {
+                    edge,
+                    neighbour,
+                    partner,
+                    transform,
+                    reversed: start.distance(end) < 1e-6 * scale,
+                }
+            })
+            .collect()
+    }
+
+    /// Computes which tiles share each edge.
+    ///
+    /// The adjacency only depends on the tiling type, not on the parameters, so it is computed
+    /// using the default parameters and stays valid when the parameters change.
+    pub fn adjacency(&self) -> Adjacency {
+        let tiling = IsohedralTiling::new(self.tiling_type());
+        let verts = tiling.vertices();
+        let n = verts.len();
+        let bounds = BoundingBox::from_points(verts);
+        let eps = 1e-6 * bounds.width().max(bounds.height());
+
+        let mut table = vec![];
+        let mut edge_neighbours = vec![];
+        let mut vertex_neighbours = vec![];
+        for aspect in 0..tiling.num_aspects() {
+            let m = tiling.aspect_transform(aspect);
+            let corners: Vec<_> = verts.iter().map(|v| m.transform_point2(*v)).collect();
+            let touching: Vec<_> = tiling
+                .tiles_in_bounds(&BoundingBox::from_points(&corners).expand(eps), &bounds)
+                .into_iter()
+                .filter(|&(t1, t2, other)| {
+                    let m = tiling.tile_transform(t1, t2, other);
+                    (t1, t2, other) != (0, 0, aspect)
+                        && verts.iter().any(|v| {
+                            let p = m.transform_point2(*v);
+                            corners.iter().any(|c| c.distance(p) < eps)
+                        })
+                })
+                .collect();
+            vertex_neighbours.push(touching);
+
+            let mut distinct = vec![];
+            for idx in 0..n {
+                let p = m.transform_point2(verts[idx]);
+                let q = m.transform_point2(verts[(idx + 1) % n]);
+                let region = BoundingBox::from_points(&[p, q]).expand(eps);
+                // The neighbour goes along the same edge, in the same direction if it is
+                // reflected with respect to this tile and in the opposite one otherwise
+                let neighbour = tiling
+                    .tiles_in_bounds(&region, &bounds)
+                    .into_iter()
+                    .filter(|&(t1, t2, other)| (t1, t2, other) != (0, 0, aspect))
+                    .find_map(|(t1, t2, other)| {
+                        let m = tiling.tile_transform(t1, t2, other);
+                        (0..n)
+                            .find(|&e| {
+                                let a = m.transform_point2(verts[e]);
+                                let b = m.transform_point2(verts[(e + 1) % n]);
+                                (a.distance(q) < eps && b.distance(p) < eps)
+                                    || (a.distance(p) < eps && b.distance(q) < eps)
+                            })
+                            .map(|e| (t1, t2, other, e))
+                    })
+                    .expect("Every edge of a tile is shared with a neighbour");
+                table.push(neighbour);
+                let (t1, t2, other, _) = neighbour;
+                if !distinct.contains(&(t1, t2, other)) {
+                    distinct.push((t1, t2, other));
+                }
+            }
+            edge_neighbours.push(distinct);
+        }
+
+        Adjacency {
+            num_edges: n,
+            table,
+            edge_neighbours,
+            vertex_neighbours,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{data::TILING_TYPES, test_utils, IsohedralTiling, TilingType};
+
+    use super::{Neighbourhood, TileCoord};
+
+    #[test]
+    fn adjacency_is_symmetric() {
+        for &t in TILING_TYPES.iter() {
+            let tiling = IsohedralTiling::new(TilingType(t));
+            let adjacency = tiling.adjacency();
+            for aspect in 0..tiling.num_aspects() {
+                let tile = TileCoord::new(2, -1, aspect);
+                for edge in 0..adjacency.num_edges() {
+                    let (other, back) = adjacency.across(tile, edge);
+                    assert_ne!(other, tile);
+                    assert_eq!(adjacency.across(other, back), (tile, edge), "IH{}", t);
+                }
+                let touching: Vec<_> = adjacency
+                    .neighbourhood(tile, Neighbourhood::Vertex)
+                    .collect();
+                assert!(adjacency
+                    .neighbourhood(tile, Neighbourhood::Edge)
+                    .all(|other| touching.contains(&other)));
+                for other in touching {
+                    assert!(adjacency
+                        .neighbourhood(other, Neighbourhood::Vertex)
+                        .any(|t| t == tile));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pairings_map_edges_onto_partners() {
+        for &t in TILING_TYPES.iter() {
+            let tiling = IsohedralTiling::new(TilingType(t));
+            // Asymmetric curves, so that edges only match if the pairing is right
+            let curves = test_utils::bumpy_edges(&tiling, dvec2(0.3, 0.2));
+            for aspect in 0..tiling.num_aspects() {
+                let m = tiling.aspect_transform(aspect);
+                let paths: Vec<Vec<_>> = tiling
+                    .edge_paths(&curves)
+                    .into_iter()
+                    .map(|path| path.iter().map(|p| m.transform_point2(*p)).collect())
+                    .collect();
+                for pairing in tiling.edge_pairings(aspect) {
+                    let (e, p) = (pairing.edge, pairing.partner);
+                    assert_eq!(tiling.ttd.edge_shape_ids[e], tiling.ttd.edge_shape_ids[p]);
+                    let mut mapped: Vec<_> = paths[e]
+                        .iter()
+                        .map(|q| pairing.transform.transform_point2(*q))
+                        .collect();
+                    if pairing.reversed {
+                        mapped.reverse();
+                    }
+                    assert_eq!(mapped.len(), paths[p].len());
+                    for (a, b) in mapped.iter().zip(&paths[p]) {
+                        assert!(a.distance(*b) < 1e-9, "IH{}: {} -> {}", t, e, p);
+                    }
+                    // The inverse transform maps the tile onto the neighbour
+                    let n = pairing.neighbour;
+                    let onto = pairing.transform.inverse() * *m;
+                    let expected = tiling.tile_transform(n.t1, n.t2, n.aspect);
+                    assert!(onto.abs_diff_eq(expected, 1e-9));
+                }
+            }
+        }
+    }
+}
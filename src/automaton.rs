@@ -0,0 +1,260 @@
+//! Cellular automata running on the tiles of a tiling.
+//!
+//! See [`Automaton`].
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    adjacency::{Neighbourhood, TileCoord},
+    torus::Torus,
+    IsohedralTiling,
+};
+
+/// The set of tiles an [`Automaton`] runs on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Domain {
+    /// The tiles of a [`Torus`], wrapping around its supercell. The torus must wrap the same
+    /// tiling as the one the automaton runs on.
+    Torus(Torus),
+    /// A finite set of tiles. Neighbours outside of the set are ignored.
+    Patch(Vec<TileCoord>),
+}
+
+/// The rule deciding the next state of each tile of an [`Automaton`].
+///
+/// Any closure taking the current state of a tile and the states of its neighbours, and
+/// returning the next state of the tile, can be used as a rule.
+pub trait Rule<S> {
+    /// Computes the next state of a tile from its current `state` and the states of its
+    /// `neighbours`.
+    fn next(&self, state: &S, neighbours: &[&S]) -> S;
+}
+
+impl<S, F> Rule<S> for F
+where
+    F: Fn(&S, &[&S]) -> S,
+{
+    fn next(&self, state: &S, neighbours: &[&S]) -> S {
+        self(state, neighbours)
+    }
+}
+
+/// A rule in the style of Conway's Game of Life, where each tile is either alive or dead.
+///
+/// A dead tile becomes alive if its number of living neighbours is in `birth`, and a living tile
+/// stays alive if its number of living neighbours is in `survival`. The Game of Life itself is
+/// `birth: vec![3], survival: vec![2, 3]` with [`Neighbourhood::Vertex`] on a grid of squares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeLike {
+    /// The numbers of living neighbours making a dead tile alive.
+    pub birth: Vec<usize>,
+    /// The numbers of living neighbours keeping a living tile alive.
+    pub survival: Vec<usize>,
+}
+
+impl Rule<bool> for LifeLike {
+    fn next(&self, state: &bool, neighbours: &[&bool]) -> bool {
+        let alive = neighbours.iter().filter(|&&&n| n).count();
+        if *state {
+            self.survival.contains(&alive)
+        } else {
+            self.birth.contains(&alive)
+        }
+    }
+}
+
+/// A cellular automaton storing a state of type `S` for each tile of a [`Domain`].
+///
+/// The neighbours of each tile are computed once when the automaton is created. Each tile only
+/// counts once as a neighbour of another tile, even when wrapping around a small torus makes it
+/// adjacent in several ways, and a tile is never its own neighbour.
+#[derive(Debug, Clone)]
+pub struct Automaton<S> {
+    tiles: Vec<TileCoord>,
+    index: HashMap<TileCoord, usize>,
+    /// The indices of the neighbours of each tile
+    neighbours: Vec<Vec<usize>>,
+    /// The torus, if the domain wraps around
+    torus: Option<Torus>,
+    states: Vec<S>,
+    generation: usize,
+}
+
+impl<S> Automaton<S> {
+    /// Creates an automaton running on `domain`, where the initial state of each tile is given by
+    /// `initial`.
+    pub fn new<F: FnMut(TileCoord) -> S>(
+        tiling: &IsohedralTiling,
+        domain: Domain,
+        neighbourhood: Neighbourhood,
+        mut initial: F,
+    ) -> Self {
+        let (tiles, torus) = match domain {
+            Domain::Torus(torus) => (torus.tiles().to_vec(), Some(torus)),
+            Domain::Patch(mut tiles) => {
+                let mut seen = HashSet::new();
+                tiles.retain(|t| seen.insert(*t));
+                (tiles, None)
+            }
+        };
+        let index: HashMap<_, _> = tiles.iter().enumerate().map(|(i, t)| (*t, i)).collect();
+
+        let neighbours = match &torus {
+            Some(torus) => tiles
+                .iter()
+                .map(|tile| {
+                    torus
+                        .neighbourhood(*tile, neighbourhood)
+                        .into_iter()
+                        .map(|other| index[&other])
+                        .collect()
+                })
+                .collect(),
+            None => {
+                let adjacency = tiling.adjacency();
+                tiles
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, tile)| {
+                        let mut list = vec![];
+                        for other in adjacency.neighbourhood(*tile, neighbourhood) {
+                            if let Some(&n) = index.get(&other) {
+                                if n != idx && !list.contains(&n) {
+                                    list.push(n);
+                                }
+                            }
+                        }
+                        list
+                    })
+                    .collect()
+            }
+        };
+
+        Self {
+            states: tiles.iter().map(|t| initial(*t)).collect(),
+            tiles,
+            index,
+            neighbours,
+            torus,
+            generation: 0,
+        }
+    }
+
+    /// The tiles of the domain, in the same order as [`Automaton::states`].
+    pub fn tiles(&self) -> &[TileCoord] {
+        &self.tiles
+    }
+
+    /// The state of every tile, in the same order as [`Automaton::tiles`].
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// The number of steps run since the automaton was created.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Returns the state of a tile, or `None` if it isn't part of the domain. On a torus, the
+    /// lattice coordinates wrap around.
+    pub fn state(&self, tile: TileCoord) -> Option<&S> {
+        let idx = self.index.get(&self.wrap(tile))?;
+        Some(&self.states[*idx])
+    }
+
+    /// Changes the state of a tile. Returns `false` if the tile isn't part of the domain. On a
+    /// torus, the lattice coordinates wrap around.
+    pub fn set_state(&mut self, tile: TileCoord, state: S) -> bool {
+        match self.index.get(&self.wrap(tile)) {
+            Some(&idx) => {
+                self.states[idx] = state;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs one step of the automaton, updating all the tiles at once.
+    pub fn step<R: Rule<S> + ?Sized>(&mut self, rule: &R) {
+        let mut neighbours = vec![];
+        let next = (0..self.tiles.len())
+            .map(|idx| {
+                neighbours.clear();
+                neighbours.extend(self.neighbours[idx].iter().map(|&n| &self.states[n]));
+                rule.next(&self.states[idx], &neighbours)
+            })
+            .collect();
+        self.states = next;
+        self.generation += 1;
+    }
+
+    /// Runs `steps` steps of the automaton.
+    pub fn run<R: Rule<S> + ?Sized>(&mut self, rule: &R, steps: usize) {
+        for _ in 0..steps {
+            self.step(rule);
+        }
+    }
+
+    /// Maps a tile to its representative in the domain, if it wraps around.
+    fn wrap(&self, tile: TileCoord) -> TileCoord {
+        match &self.torus {
+            Some(torus) => torus.wrap(tile),
+            None => tile,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        adjacency::{Neighbourhood, TileCoord},
+        supercell::Supercell,
+        IsohedralTiling, TilingType,
+    };
+
+    use super::{Automaton, Domain, LifeLike};
+
+    #[test]
+    fn automaton_counts_neighbours() {
+        let tiling = IsohedralTiling::new(TilingType(41));
+        let torus = Domain::Torus(tiling.torus(Supercell::diagonal(5, 4)));
+        let mut counts = Automaton::new(&tiling, torus, Neighbourhood::Vertex, |_| 0);
+        assert_eq!(counts.tiles().len(), 20);
+        counts.step(&|_: &usize, n: &[&usize]| n.len());
+        assert!(counts.states().iter().all(|&c| c == 8));
+        assert!(counts.set_state(TileCoord::new(6, -1, 0), 1));
+        assert_eq!(counts.state(TileCoord::new(1, 3, 0)), Some(&1));
+
+        // On a skewed torus of 5 squares, all the other squares touch each one
+        let skewed = tiling.torus(Supercell::new([[2, 1], [-1, 2]]).unwrap());
+        let mut counts =
+            Automaton::new(&tiling, Domain::Torus(skewed), Neighbourhood::Vertex, |_| 0);
+        assert_eq!(counts.tiles().len(), 5);
+        counts.step(&|_: &usize, n: &[&usize]| n.len());
+        assert!(counts.states().iter().all(|&c| c == 4));
+
+        // On a patch, the tiles on the border have fewer neighbours
+        let patch: Vec<_> = (0..3).map(|t1| TileCoord::new(t1, 0, 0)).collect();
+        let mut counts = Automaton::new(&tiling, Domain::Patch(patch), Neighbourhood::Edge, |_| 0);
+        counts.step(&|_: &usize, n: &[&usize]| n.len());
+        let mut sorted = counts.states().to_vec();
+        sorted.sort_unstable();
+        assert_eq!(sorted, [1, 1, 2]);
+        assert_eq!(counts.state(TileCoord::new(5, 0, 0)), None);
+
+        // A lonely cell dies, a full torus dies of overcrowding
+        let life = LifeLike {
+            birth: vec![3],
+            survival: vec![2, 3],
+        };
+        let torus = Domain::Torus(tiling.torus(Supercell::diagonal(6, 6)));
+        let mut lonely = Automaton::new(&tiling, torus.clone(), Neighbourhood::Vertex, |t| {
+            t == TileCoord::new(2, 2, 0)
+        });
+        lonely.step(&life);
+        assert!(lonely.states().iter().all(|&s| !s));
+        let mut full = Automaton::new(&tiling, torus, Neighbourhood::Vertex, |_| true);
+        full.run(&life, 2);
+        assert!(full.states().iter().all(|&s| !s));
+        assert_eq!(full.generation(), 2);
+    }
+}
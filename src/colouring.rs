@@ -0,0 +1,551 @@
+//! Perfect colourings of isohedral tilings with any number of colours.
+//!
+//! A colouring of the tiles is *perfect* when every symmetry of the tiling maps all the tiles of
+//! a colour onto tiles of a single colour, i.e. when the symmetries of the tiling permute the
+//! colours. [`IsohedralTiling::colour`] provides one such colouring with at most three colours,
+//! while [`IsohedralTiling::perfect_colourings`] finds all of them for any number of colours.
+//!
+//! The symmetries considered are the ones generated by the two translation vectors and the aspect
+//! transforms, which form the symmetry group of the tiling as long as the prototile itself isn't
+//! symmetric.
+//!
+//! The perfect colourings with `k` colours match the subgroups of index `k` of the symmetry group.
+//! Each of these subgroups contains the translations by `j * t1` and `j * t2` for some period
+//! `j <= k`, so the colour of a tile only depends on its lattice coordinates modulo `j` and on
+//! its aspect. The colourings are thus found as the ways of splitting these `j * j * num_aspects`
+//! tiles into `k` blocks that the symmetries map onto each other.
+use std::{collections::HashSet, fmt};
+
+use glam::DVec2;
+
+use crate::{adjacency::TileCoord, geometry, IsohedralTiling};
+
+/// The largest period tried by [`IsohedralTiling::proper_colouring`].
+const MAX_PERIOD: usize = 4;
+
+/// A colouring of the tiles which repeats every `period` copies of each translation vector.
+///
+/// See [`IsohedralTiling::perfect_colourings`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PeriodicColouring {
+    period: usize,
+    num_aspects: usize,
+    num_colours: usize,
+    /// The colour of each tile, indexed by `(t1 * period + t2) * num_aspects + aspect`
+    table: Vec<usize>,
+}
+
+impl PeriodicColouring {
+    /// The number of copies of each translation vector after which the colouring repeats.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// The number of colours used.
+    pub fn num_colours(&self) -> usize {
+        self.num_colours
+    }
+
+    /// Returns the colour of a tile, between `0` and [`num_colours`] (excluded). The parameters
+    /// have the same meaning as for [`IsohedralTiling::colour`].
+    ///
+    /// [`num_colours`]: PeriodicColouring::num_colours
+    pub fn colour(&self, t1: isize, t2: isize, aspect: usize) -> usize {
+        let j = self.period as isize;
+        let idx = (t1.rem_euclid(j) * j + t2.rem_euclid(j)) as usize * self.num_aspects + aspect;
+        self.table[idx]
+    }
+
+    /// Returns a basis of the sublattice of translations preserving the colour of every tile, in
+    /// lattice coordinates: each vector `(a, b)` stands for the translation `a * t1 + b * t2`.
+    ///
+    /// The basis is in Hermite normal form: the first vector is `(a, b)` with `a > 0` and
+    /// `0 <= b < d`, and the second one is `(0, d)`.
+    pub fn translations(&self) -> [[isize; 2]; 2] {
+        let j = self.period as isize;
+        // The period always gives preserved translations, so both searches succeed
+        let d = (1..=j).find(|&b| self.preserves(0, b)).unwrap_or(j);
+        let (a, b) = (1..=j)
+            .flat_map(|a| (0..d).map(move |b| (a, b)))
+            .find(|&(a, b)| self.preserves(a, b))
+            .unwrap_or((j, 0));
+        [[a, b], [0, d]]
+    }
+
+    /// Whether the translation `a * t1 + b * t2` preserves the colour of every tile.
+    fn preserves(&self, a: isize, b: isize) -> bool {
+        let j = self.period as isize;
+        (0..j).all(|t1| {
+            (0..j).all(|t2| {
+                (0..self.num_aspects)
+                    .all(|asp| self.colour(t1 + a, t2 + b, asp) == self.colour(t1, t2, asp))
+            })
+        })
+    }
+}
+
+/// Two tiles sharing an edge have the same colour.
+///
+/// See [`IsohedralTiling::check_colouring`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColouringConflict {
+    /// The first tile.
+    pub tile: TileCoord,
+    /// The index of the shared edge in the first tile.
+    pub edge: usize,
+    /// The tile on the other side of the edge.
+    pub neighbour: TileCoord,
+    /// The colour of both tiles.
+    pub colour: usize,
+}
+
+impl fmt::Display for ColouringConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tiles {:?} and {:?} share edge {} and both have colour {}",
+            self.tile, self.neighbour, self.edge, self.colour
+        )
+    }
+}
+
+impl std::error::Error for ColouringConflict {}
+
+/// How the aspect transforms act on the tiles: applying aspect transform `b` to the tile with
+/// lattice coordinates `t` and aspect `a` gives the tile `linear[b] * t + images[b][a].0` with
+/// aspect `images[b][a].1`.
+#[derive(Debug, Clone)]
+pub(crate) struct AspectAction {
+    /// The action of each aspect transform on the lattice, as the images of `(1, 0)` and `(0, 1)`
+    pub(crate) linear: Vec<[[isize; 2]; 2]>,
+    pub(crate) images: Vec<Vec<([isize; 2], usize)>>,
+}
+
+impl IsohedralTiling {
+    /// Returns all the perfect colourings of the tiling using exactly `num_colours` colours.
+    ///
+    /// Colourings that only differ by a renaming of the colours are only returned once, but
+    /// colourings mapped onto each other by a symmetry of the tiling are all returned. The
+    /// colourings are sorted by increasing period.
+    ///
+    /// The search is exhaustive, so it gets slow as the number of colours grows, especially for
+    /// tiling types with many aspects. An empty list is returned if the current parameters are
+    /// degenerate.
+    ///
+    /// See the [`colouring`](crate::colouring) module for details.
+    pub fn perfect_colourings(&self, num_colours: usize) -> Vec<PeriodicColouring> {
+        let action = match self.aspect_action() {
+            Some(action) => action,
+            None => return vec![],
+        };
+
+        let na = self.num_aspects();
+        let mut colourings = vec![];
+        for j in 1..=num_colours {
+            let n = j * j * na;
+            if !n.is_multiple_of(num_colours) {
+                continue;
+            }
+
+            let ji = j as isize;
+            let index = |t: [isize; 2], aspect: usize| {
+                (t[0].rem_euclid(ji) * ji + t[1].rem_euclid(ji)) as usize * na + aspect
+            };
+            let tiles: Vec<_> = (0..ji)
+                .flat_map(|t1| (0..ji).flat_map(move |t2| (0..na).map(move |a| ([t1, t2], a))))
+                .collect();
+
+            let mut gens = vec![
+                tiles
+                    .iter()
+                    .map(|&(t, a)| index([t[0] + 1, t[1]], a))
+                    .collect(),
+                tiles
+                    .iter()
+                    .map(|&(t, a)| index([t[0], t[1] + 1], a))
+                    .collect(),
+            ];
+            for (linear, images) in action.linear.iter().zip(&action.images) {
+                gens.push(
+                    tiles
+                        .iter()
+                        .map(|&(t, a)| {
+                            let (offset, aspect) = images[a];
+                            let x = t[0] * linear[0][0] + t[1] * linear[1][0] + offset[0];
+                            let y = t[0] * linear[0][1] + t[1] * linear[1][1] + offset[1];
+                            index([x, y], aspect)
+                        })
+                        .collect(),
+                );
+            }
+
+            for table in block_systems(&gens, n / num_colours) {
+                let colouring = PeriodicColouring {
+                    period: j,
+                    num_aspects: na,
+                    num_colours,
+                    table,
+                };
+                // Colourings with a smaller period were already found with that period
+                if (1..ji).all(|p| {
+                    ji % p != 0 || !colouring.preserves(p, 0) || !colouring.preserves(0, p)
+                }) {
+                    colourings.push(colouring);
+                }
+            }
+        }
+
+        colourings
+    }
+
+    /// Checks that the colouring `colour` never gives the same colour to two tiles sharing an
+    /// edge, for all the tiles whose lattice coordinates are between `-radius` and `radius`.
+    ///
+    /// For a periodic colouring, such as the one given by [`IsohedralTiling::colour`], checking a
+    /// radius of one period is enough to check the whole plane.
+    pub fn check_colouring<F: Fn(TileCoord) -> usize>(
+        &self,
+        colour: F,
+        radius: isize,
+    ) -> Result<(), ColouringConflict> {
+        let adjacency = self.adjacency();
+        for t1 in -radius..=radius {
+            for t2 in -radius..=radius {
+                for aspect in 0..self.num_aspects() {
+                    let tile = TileCoord::new(t1, t2, aspect);
+                    let c = colour(tile);
+                    for edge in 0..adjacency.num_edges() {
+                        let (neighbour, _) = adjacency.across(tile, edge);
+                        if colour(neighbour) == c {
+                            return Err(ColouringConflict {
+                                tile,
+                                edge,
+                                neighbour,
+                                colour: c,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a colouring where tiles sharing an edge always have different colours, using as
+    /// few colours as possible.
+    ///
+    /// The colourings considered repeat after at most 4 copies of each translation vector, and the
+    /// one with the fewest colours, then with the smallest period, is returned. Unlike the ones
+    /// returned by [`perfect_colourings`], this colouring isn't necessarily preserved by the
+    /// symmetries of the tiling.
+    ///
+    /// [`perfect_colourings`]: IsohedralTiling::perfect_colourings
+    pub fn proper_colouring(&self) -> PeriodicColouring {
+        let adjacency = self.adjacency();
+        let na = self.num_aspects();
+
+        (1..)
+            .find_map(|num_colours| {
+                (1..=MAX_PERIOD).find_map(|j| {
+                    let ji = j as isize;
+                    let index = |t: TileCoord| {
+                        (t.t1.rem_euclid(ji) * ji + t.t2.rem_euclid(ji)) as usize * na + t.aspect
+                    };
+                    let neighbours: Vec<Vec<_>> = (0..j * j * na)
+                        .map(|idx| {
+                            let t = idx / na;
+                            let tile = TileCoord::new((t / j) as isize, (t % j) as isize, idx % na);
+                            adjacency.neighbours(tile).map(index).collect()
+                        })
+                        .collect();
+                    colour_graph(&neighbours, num_colours).map(|table| PeriodicColouring {
+                        period: j,
+                        num_aspects: na,
+                        num_colours,
+                        table,
+                    })
+                })
+            })
+            .expect("Tilings can always be coloured with 7 colours")
+    }
+
+    /// Computes how the aspect transforms act on the tiles, by following the centroid of the
+    /// prototile. Returns `None` if the tiles can't be told apart.
+    pub(crate) fn aspect_action(&self) -> Option<AspectAction> {
+        let det = self.t1.perp_dot(self.t2);
+        if det.abs() < 1e-9 {
+            return None;
+        }
+        let lattice = |v: DVec2| -> Option<[isize; 2]> {
+            let x = v.perp_dot(self.t2) / det;
+            let y = self.t1.perp_dot(v) / det;
+            let (rx, ry) = (x.round(), y.round());
+            if (x - rx).abs() < 1e-6 && (y - ry).abs() < 1e-6 {
+                Some([rx as isize, ry as isize])
+            } else {
+                None
+            }
+        };
+
+        let c0 = geometry::centroid(self.vertices());
+        let centres: Vec<_> = self.aspects[..self.num_aspects()]
+            .iter()
+            .map(|m| m.transform_point2(c0))
+            .collect();
+
+        let mut action = AspectAction {
+            linear: vec![],
+            images: vec![],
+        };
+        for m in &self.aspects[..self.num_aspects()] {
+            action.linear.push([
+                lattice(m.transform_vector2(self.t1))?,
+                lattice(m.transform_vector2(self.t2))?,
+            ]);
+            let images = centres
+                .iter()
+                .map(|c| {
+                    let p = m.transform_point2(*c);
+                    centres
+                        .iter()
+                        .enumerate()
+                        .find_map(|(aspect, q)| Some((lattice(p - *q)?, aspect)))
+                })
+                .collect::<Option<_>>()?;
+            action.images.push(images);
+        }
+
+        Some(action)
+    }
+}
+
+/// Finds all the partitions of the points `0..n` into blocks of `block_size` points which are
+/// preserved by the permutations `gens`, returned as canonical labellings (blocks are numbered in
+/// order of first appearance). The action of `gens` must be transitive.
+fn block_systems(gens: &[Vec<usize>], block_size: usize) -> Vec<Vec<usize>> {
+    let n = gens[0].len();
+    let size = |labels: &[usize]| labels.iter().filter(|&&l| l == labels[0]).count();
+
+    // Every block system is generated by the pairs in the block containing `0`, so all of them
+    // can be reached by merging the smallest systems containing each pair `(0, y)`
+    let mut atoms = vec![];
+    let mut seen = HashSet::new();
+    for y in 1..n {
+        let atom = close(gens, &[(0, y)]);
+        if block_size.is_multiple_of(size(&atom)) && seen.insert(atom.clone()) {
+            atoms.push(atom);
+        }
+    }
+
+    let mut found = vec![(0..n).collect::<Vec<_>>()];
+    seen.insert(found[0].clone());
+    found.extend(atoms.iter().cloned());
+    let mut next = 1;
+    while next < found.len() {
+        let current = found[next].clone();
+        next += 1;
+        for atom in &atoms {
+            // Skip atoms already included in the current system
+            if (0..n).all(|y| atom[y] != atom[0] || current[y] == current[0]) {
+                continue;
+            }
+            let pairs: Vec<_> = [&current, atom]
+                .iter()
+                .flat_map(|labels| {
+                    let mut first = vec![usize::MAX; n];
+                    (0..n)
+                        .map(|y| {
+                            if first[labels[y]] == usize::MAX {
+                                first[labels[y]] = y;
+                            }
+                            (first[labels[y]], y)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            let joined = close(gens, &pairs);
+            if block_size.is_multiple_of(size(&joined)) && seen.insert(joined.clone()) {
+                found.push(joined);
+            }
+        }
+    }
+
+    found.retain(|labels| size(labels) == block_size);
+    found
+}
+
+/// Colours the vertices of the connected graph given by `neighbours` with `num_colours` colours,
+/// such that neighbours never have the same colour, by backtracking. Returns `None` if there is no
+/// such colouring.
+fn colour_graph(neighbours: &[Vec<usize>], num_colours: usize) -> Option<Vec<usize>> {
+    let n = neighbours.len();
+    if (0..n).any(|v| neighbours[v].contains(&v)) {
+        return None;
+    }
+
+    // Visit the vertices in breadth-first order, so that each one has coloured neighbours and
+    // conflicts are found early
+    let mut order = vec![0];
+    let mut visited = vec![false; n];
+    visited[0] = true;
+    let mut next = 0;
+    while next < order.len() {
+        for &w in &neighbours[order[next]] {
+            if !visited[w] {
+                visited[w] = true;
+                order.push(w);
+            }
+        }
+        next += 1;
+    }
+
+    let mut colours = vec![usize::MAX; n];
+    // The largest colour used before each step, to avoid trying colourings that only differ by a
+    // renaming of the colours
+    let mut used = vec![0; n + 1];
+    let mut step = 0;
+    while step < n {
+        let v = order[step];
+        let start = colours[v].wrapping_add(1);
+        let limit = num_colours.min(used[step] + 1);
+        match (start..limit).find(|&c| neighbours[v].iter().all(|&w| colours[w] != c)) {
+            Some(c) => {
+                colours[v] = c;
+                used[step + 1] = used[step].max(c + 1);
+                step += 1;
+            }
+            None => {
+                colours[v] = usize::MAX;
+                if step == 0 {
+                    return None;
+                }
+                step -= 1;
+            }
+        }
+    }
+
+    Some(colours)
+}
+
+/// Computes the finest partition merging the given pairs of points which is preserved by the
+/// permutations `gens`, using a union-find structure.
+fn close(gens: &[Vec<usize>], pairs: &[(usize, usize)]) -> Vec<usize> {
+    let n = gens[0].len();
+    let mut parent: Vec<_> = (0..n).collect();
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+
+    let mut pending = vec![];
+    for &(a, b) in pairs {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        if ra != rb {
+            parent[ra] = rb;
+            pending.push((a, b));
+        }
+    }
+    // The merged pairs connect each block, so mapping them is enough to map the whole blocks
+    while let Some((a, b)) = pending.pop() {
+        for g in gens {
+            let (ra, rb) = (find(&mut parent, g[a]), find(&mut parent, g[b]));
+            if ra != rb {
+                parent[ra] = rb;
+                pending.push((g[a], g[b]));
+            }
+        }
+    }
+
+    let mut labels = vec![usize::MAX; n];
+    let mut count = 0;
+    (0..n)
+        .map(|x| {
+            let root = find(&mut parent, x);
+            if labels[root] == usize::MAX {
+                labels[root] = count;
+                count += 1;
+            }
+            labels[root]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        adjacency::TileCoord, data::TILING_TYPES, geometry, get_tiling_type, IsohedralTiling,
+        TilingType,
+    };
+
+    #[test]
+    fn counts_translation_colourings() {
+        // With translations only, colourings match the sublattices of the same index
+        let tiling = TILING_TYPES
+            .iter()
+            .map(|&t| IsohedralTiling::new(TilingType(t)))
+            .find(|t| t.num_aspects() == 1)
+            .unwrap();
+        assert_eq!(tiling.perfect_colourings(1).len(), 1);
+        assert_eq!(tiling.perfect_colourings(2).len(), 3);
+        assert_eq!(tiling.perfect_colourings(3).len(), 4);
+        assert_eq!(tiling.perfect_colourings(4).len(), 7);
+    }
+
+    #[test]
+    fn colourings_are_perfect() {
+        let tiling = IsohedralTiling::new(get_tiling_type(4));
+        let c0 = geometry::centroid(tiling.vertices());
+        let tiles: Vec<_> = (-3..=3)
+            .flat_map(|t1| (-3..=3).map(move |t2| (t1, t2)))
+            .flat_map(|(t1, t2)| (0..tiling.num_aspects()).map(move |a| (t1, t2, a)))
+            .map(|(t1, t2, a)| ((t1, t2, a), tiling.tile_transform(t1, t2, a)))
+            .collect();
+
+        for k in 2..=4 {
+            let colourings = tiling.perfect_colourings(k);
+            assert!(!colourings.is_empty());
+            for colouring in colourings {
+                for aspect in 0..tiling.num_aspects() {
+                    // The symmetry must send all the tiles of a colour to tiles of one colour
+                    let g = tiling.aspect_transform(aspect);
+                    let mut image = vec![None; k];
+                    for &((t1, t2, a), m) in &tiles {
+                        let p = g.transform_point2(m.transform_point2(c0));
+                        if let Some(((u1, u2, b), _)) = tiles
+                            .iter()
+                            .find(|(_, m)| m.transform_point2(c0).distance(p) < 1e-6)
+                        {
+                            let from = colouring.colour(t1, t2, a);
+                            let to = colouring.colour(*u1, *u2, *b);
+                            assert_eq!(*image[from].get_or_insert(to), to);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn checks_proper_colourings() {
+        for &t in TILING_TYPES.iter() {
+            let tiling = IsohedralTiling::new(TilingType(t));
+            let builtin = |c: TileCoord| tiling.colour(c.t1, c.t2, c.aspect) as usize;
+            assert_eq!(tiling.check_colouring(builtin, 6), Ok(()), "IH{}", t);
+
+            let colouring = tiling.proper_colouring();
+            assert!(colouring.num_colours() <= 3);
+            let proper = |c: TileCoord| colouring.colour(c.t1, c.t2, c.aspect);
+            assert_eq!(tiling.check_colouring(proper, 8), Ok(()), "IH{}", t);
+        }
+
+        // The quadrilaterals of IH41 can be coloured like a chessboard, but not with one colour
+        let quads = IsohedralTiling::new(TilingType(41));
+        assert_eq!(quads.proper_colouring().num_colours(), 2);
+        let conflict = quads.check_colouring(|_| 0, 1).unwrap_err();
+        assert_eq!(conflict.colour, 0);
+    }
+}
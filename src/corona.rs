@@ -0,0 +1,104 @@
+//! Growing patches of tiles outward from a seed tile, one corona at a time.
+//!
+//! The first corona of a tile is the ring of tiles around it, the second corona is the ring
+//! around the first one, and so on. See [`IsohedralTiling::coronas`].
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    adjacency::{Adjacency, Neighbourhood, TileCoord},
+    IsohedralTiling,
+};
+
+/// A tile returned by [`Coronas`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoronaTile {
+    /// The position of the tile.
+    pub tile: TileCoord,
+    /// The corona the tile belongs to: `0` for the seed tile, `1` for its neighbours, etc.
+    pub corona: usize,
+}
+
+/// An iterator over the tiles around a seed tile, in order of increasing corona.
+///
+/// See [`IsohedralTiling::coronas`].
+#[derive(Debug, Clone)]
+pub struct Coronas {
+    adjacency: Adjacency,
+    neighbourhood: Neighbourhood,
+    max_corona: usize,
+    seen: HashSet<TileCoord>,
+    queue: VecDeque<CoronaTile>,
+}
+
+impl Iterator for Coronas {
+    type Item = CoronaTile;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.queue.pop_front()?;
+        if current.corona < self.max_corona {
+            for tile in self
+                .adjacency
+                .neighbourhood(current.tile, self.neighbourhood)
+            {
+                if self.seen.insert(tile) {
+                    self.queue.push_back(CoronaTile {
+                        tile,
+                        corona: current.corona + 1,
+                    });
+                }
+            }
+        }
+        Some(current)
+    }
+}
+
+impl IsohedralTiling {
+    /// Iterates over the tiles around `seed`, up to and including the corona `max_corona`.
+    ///
+    /// The seed is returned first, then all the tiles of its first corona, then all the tiles of
+    /// the second one, etc. The corona of a tile is the smallest number of steps from neighbour to
+    /// neighbour needed to reach it from the seed, where neighbours are defined by
+    /// `neighbourhood`. Coronas in the sense of Heesch use [`Neighbourhood::Vertex`].
+    pub fn coronas(
+        &self,
+        seed: TileCoord,
+        max_corona: usize,
+        neighbourhood: Neighbourhood,
+    ) -> Coronas {
+        Coronas {
+            adjacency: self.adjacency(),
+            neighbourhood,
+            max_corona,
+            seen: HashSet::from([seed]),
+            queue: VecDeque::from([CoronaTile {
+                tile: seed,
+                corona: 0,
+            }]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        adjacency::{Neighbourhood, TileCoord},
+        get_tiling_type, IsohedralTiling,
+    };
+
+    #[test]
+    fn coronas_grow_outward() {
+        let tiling = IsohedralTiling::new(get_tiling_type(40));
+        let seed = TileCoord::new(2, 3, 0);
+
+        // Quadrilaterals in a lattice: 4 edge neighbours and 8 vertex neighbours
+        let tiles: Vec<_> = tiling.coronas(seed, 2, Neighbourhood::Edge).collect();
+        let count = |k| tiles.iter().filter(|t| t.corona == k).count();
+        assert_eq!(tiles[0].tile, seed);
+        assert_eq!((count(0), count(1), count(2)), (1, 4, 8));
+        assert!(tiles.windows(2).all(|w| w[0].corona <= w[1].corona));
+
+        let tiles: Vec<_> = tiling.coronas(seed, 2, Neighbourhood::Vertex).collect();
+        let count = |k| tiles.iter().filter(|t| t.corona == k).count();
+        assert_eq!((count(0), count(1), count(2)), (1, 8, 16));
+    }
+}
@@ -0,0 +1,165 @@
+//! The translational unit cell and a fundamental domain of a tiling, along with the pieces of
+//! tiles inside them.
+//!
+//! See [`IsohedralTiling::unit_cell`] and [`IsohedralTiling::fundamental_domain`].
+use std::f64::consts::{PI, TAU};
+
+use glam::{dvec2, DMat2, DMat3, DVec2};
+
+use crate::{
+    adjacency::TileCoord,
+    geometry::{self, BoundingBox},
+    IsohedralTiling,
+};
+
+/// The part of a tile inside a [`Cell`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TilePiece {
+    /// The tile the piece comes from.
+    pub tile: TileCoord,
+    /// The part of the tile's outline inside the cell.
+    pub polygon: Vec<DVec2>,
+}
+
+/// A region of the plane, with the pieces of tiles covering it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    /// The outline of the region.
+    pub polygon: Vec<DVec2>,
+    /// The pieces of the tiles inside the region, which cover it exactly.
+    pub pieces: Vec<TilePiece>,
+}
+
+impl IsohedralTiling {
+    /// Computes the unit cell of the tiling: the parallelogram with corners `0`, `t1`, `t1 + t2`
+    /// and `t2`, along with the pieces of the tiles inside it.
+    ///
+    /// Translating the unit cell by the lattice covers the plane exactly, so it holds everything
+    /// needed to draw the tiling, for instance as a repeating texture. See
+    /// [`IsohedralTiling::outline`] for the format of `edges`.
+    pub fn unit_cell(&self, edges: &[Vec<DVec2>]) -> Cell {
+        let polygon = vec![DVec2::ZERO, self.t1, self.t1 + self.t2, self.t2];
+        let outline = self.outline(edges);
+        let bounds = BoundingBox::from_points(&outline);
+        let min_area = 1e-9 * self.unit_cell_area();
+
+        let pieces = self
+            .tiles_in_bounds(&BoundingBox::from_points(&polygon), &bounds)
+            .into_iter()
+            .filter_map(|(t1, t2, aspect)| {
+                let m = self.tile_transform(t1, t2, aspect);
+                let tile: Vec<_> = outline.iter().map(|p| m.transform_point2(*p)).collect();
+                let piece = geometry::clip_convex(&tile, &polygon);
+                (geometry::area(&piece) > min_area).then(|| TilePiece {
+                    tile: TileCoord::new(t1, t2, aspect),
+                    polygon: piece,
+                })
+            })
+            .collect();
+
+        Cell { polygon, pieces }
+    }
+
+    /// Computes a fundamental domain of the symmetries of the tiling: a region whose copies by
+    /// all the symmetries cover the plane exactly. See [`IsohedralTiling::outline`] for the
+    /// format of `edges`.
+    ///
+    /// This is the first tile (with lattice coordinates `(0, 0)` and aspect `0`) when it has no
+    /// symmetries of its own, and otherwise the part of that tile inside a wedge going from its
+    /// centre of symmetry, between two of its mirror lines if it has some. The cell has a single
+    /// piece, which is the whole domain.
+    pub fn fundamental_domain(&self, edges: &[Vec<DVec2>]) -> Cell {
+        let m = *self.aspect_transform(0);
+        let outline: Vec<_> = self
+            .outline(edges)
+            .iter()
+            .map(|p| m.transform_point2(*p))
+            .collect();
+
+        let symmetries = self.tile_symmetries();
+        if symmetries.len() == 1 {
+            return Cell {
+                polygon: outline.clone(),
+                pieces: vec![TilePiece {
+                    tile: TileCoord::new(0, 0, 0),
+                    polygon: outline,
+                }],
+            };
+        }
+
+        let rotations = symmetries.iter().filter(|s| s.determinant() > 0.0).count();
+        let rotation = symmetries
+            .iter()
+            .find(|s| s.determinant() > 0.0 && !s.abs_diff_eq(DMat3::IDENTITY, 1e-9));
+        let mirror = symmetries.iter().find(|s| s.determinant() < 0.0);
+        // The fixed point of the rotation x -> linear * x + offset, or else a point on the mirror
+        let centre = match rotation {
+            Some(r) => {
+                let linear = DMat2::from_cols(r.x_axis.truncate(), r.y_axis.truncate());
+                (DMat2::IDENTITY - linear).inverse() * r.z_axis.truncate()
+            }
+            None => mirror.expect("The tile has a symmetry").z_axis.truncate() / 2.0,
+        };
+        // The wedge starts along a mirror line, and ends along the next one if there is one
+        let start = mirror.map_or(0.0, |s| s.x_axis.y.atan2(s.x_axis.x) / 2.0);
+        let angle = if mirror.is_some() {
+            PI / rotations as f64
+        } else {
+            TAU / rotations as f64
+        };
+
+        // A convex polygon covering the part of the tile inside the wedge
+        let radius = 2.0
+            * outline
+                .iter()
+                .map(|p| p.distance(centre))
+                .fold(0.0, f64::max);
+        let mut wedge = vec![centre];
+        wedge.extend((0..=4).map(|i| {
+            let a = start + angle * i as f64 / 4.0;
+            centre + dvec2(a.cos(), a.sin()) * radius / (angle / 8.0).cos()
+        }));
+
+        let polygon = geometry::clip_convex(&outline, &wedge);
+        Cell {
+            polygon: polygon.clone(),
+            pieces: vec![TilePiece {
+                tile: TileCoord::new(0, 0, 0),
+                polygon,
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{data::TILING_TYPES, geometry, test_utils, IsohedralTiling, TilingType};
+
+    #[test]
+    fn cells_are_covered_by_pieces() {
+        for &t in TILING_TYPES.iter() {
+            let tiling = IsohedralTiling::new(TilingType(t));
+            let edges = test_utils::bumpy_edges(&tiling, dvec2(0.3, 0.1));
+            let tile_area = geometry::area(&tiling.outline(&edges));
+
+            let cell = tiling.unit_cell(&edges);
+            let total: f64 = cell.pieces.iter().map(|p| geometry::area(&p.polygon)).sum();
+            assert!((total - tiling.unit_cell_area()).abs() < 1e-9, "IH{}", t);
+            assert!(cell.pieces.len() >= tiling.num_aspects());
+
+            // The symmetries of the tile split it into equal parts
+            let domain = tiling.fundamental_domain(&edges);
+            let parts = tile_area / geometry::area(&domain.polygon);
+            let symmetries = tiling.tile_symmetries().len();
+            assert!(
+                (parts - symmetries as f64).abs() < 1e-9,
+                "IH{}: {}",
+                t,
+                parts
+            );
+            assert_eq!(domain.pieces.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Escherization: finding the isohedral tiles that best approximate a given shape.
+//!
+//! This is the problem described by Kaplan and Salesin in "Escherization" (SIGGRAPH 2000): given
+//! a closed target outline, search all the isohedral tiling types, their parameters and their
+//! edge curves for the prototile that looks the most like the target.
+//!
+//! See [`escherize`].
+use glam::{dvec2, DVec2};
+
+use crate::{
+    data::TILING_TYPES,
+    optimize::{DistanceToPolygon, OptimizeOptions},
+    EdgeShape, IsohedralTiling, TilingType,
+};
+
+/// The settings of the Escherization search.
+#[derive(Debug, Clone)]
+pub struct EscherizeOptions {
+    /// The tiling types to try. Defaults to all the valid tiling types.
+    pub tiling_types: Vec<TilingType>,
+    /// The number of control points added inside each edge curve (except for `I` edges, which
+    /// stay straight). More points allow finer details but make the search slower.
+    pub control_points: usize,
+    /// The maximum number of candidates returned.
+    pub max_candidates: usize,
+    /// The settings used to optimise each tiling type.
+    pub optimize: OptimizeOptions,
+}
+
+impl Default for EscherizeOptions {
+    fn default() -> Self {
+        Self {
+            tiling_types: TILING_TYPES.iter().map(|&t| TilingType(t)).collect(),
+            control_points: 3,
+            max_candidates: 10,
+            optimize: OptimizeOptions {
+                max_iterations: 2000,
+                optimize_edges: true,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// A tile found by [`escherize`].
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// The tiling type of the candidate.
+    pub tiling_type: TilingType,
+    /// The parameters of the prototile.
+    pub parameters: [f64; 6],
+    /// The edge curves of the prototile, in the format described in
+    /// [`IsohedralTiling::outline`].
+    pub edges: Vec<Vec<DVec2>>,
+    /// How far the prototile is from the target shape, as measured by
+    /// [`geometry::shape_distance`](crate::geometry::shape_distance). Lower is better.
+    pub score: f64,
+}
+
+impl Candidate {
+    /// Creates a tiling set up with the type and parameters of this candidate.
+    pub fn tiling(&self) -> IsohedralTiling {
+        let mut tiling = IsohedralTiling::new(self.tiling_type);
+        tiling.set_parameters(&self.parameters);
+        tiling
+    }
+}
+
+/// Searches for the isohedral prototiles that best approximate the closed outline `target`.
+///
+/// Each tiling type in `options` is optimised independently, starting from its default
+/// parameters and straight edges, and the best candidates are returned, best first.
+///
+/// This can take a while, as a full optimisation is run for every tiling type.
+pub fn escherize(target: &[DVec2], options: &EscherizeOptions) -> Vec<Candidate> {
+    let objective = DistanceToPolygon {
+        target: target.to_vec(),
+    };
+
+    let mut candidates: Vec<_> = options
+        .tiling_types
+        .iter()
+        .filter_map(|&tiling_type| {
+            let tiling = IsohedralTiling::new(tiling_type);
+            let edges = initial_edges(&tiling, options.control_points);
+            let result = tiling.optimize(&objective, &edges, &options.optimize)?;
+            Some(Candidate {
+                tiling_type,
+                parameters: result.parameters,
+                edges: result.edges,
+                score: result.value,
+            })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+    candidates.truncate(options.max_candidates);
+    candidates
+}
+
+/// Straight edge curves with evenly spaced control points.
+fn initial_edges(tiling: &IsohedralTiling, control_points: usize) -> Vec<Vec<DVec2>> {
+    (0..tiling.num_edge_shapes())
+        .map(|idx| {
+            let n = if tiling.edge_shape(idx) == EdgeShape::I {
+                0
+            } else {
+                control_points
+            };
+            (0..n + 2)
+                .map(|k| dvec2(k as f64 / (n + 1) as f64, 0.0))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{get_tiling_type, test_utils, IsohedralTiling};
+
+    use super::{escherize, EscherizeOptions};
+
+    #[test]
+    fn finds_matching_tile() {
+        // Use an actual tile as the target, so a perfect match exists
+        let source = IsohedralTiling::new(get_tiling_type(6));
+        let curves = test_utils::bumpy_edges(&source, dvec2(0.5, 0.15));
+        let target = source.outline(&curves);
+
+        let options = EscherizeOptions {
+            tiling_types: [0, 6, 40].iter().map(|&n| get_tiling_type(n)).collect(),
+            control_points: 1,
+            ..Default::default()
+        };
+        let candidates = escherize(&target, &options);
+        assert_eq!(candidates.len(), 3);
+        assert!(candidates[0].score <= candidates[1].score);
+        assert!(candidates[0].score < 0.05, "{:?}", candidates[0]);
+    }
+}
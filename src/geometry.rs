@@ -0,0 +1,427 @@
+//! Basic geometric measures on closed polygons.
+//!
+//! All the functions in this module take a polygon as a slice of points, where the last point is
+//! implicitly connected back to the first one (i.e. the first point should *not* be repeated at
+//! the end). They work both on the straight-edged prototile returned by
+//! [`IsohedralTiling::vertices`] and on the curved outline returned by
+//! [`IsohedralTiling::outline`].
+//!
+//! [`IsohedralTiling::vertices`]: crate::IsohedralTiling::vertices
+//! [`IsohedralTiling::outline`]: crate::IsohedralTiling::outline
+use glam::{dvec2, DMat3, DVec2};
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// The lower-left corner of the box.
+    pub min: DVec2,
+    /// The upper-right corner of the box.
+    pub max: DVec2,
+}
+
+impl BoundingBox {
+    /// Computes the smallest box containing all the given points.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `points` is empty.
+    pub fn from_points(points: &[DVec2]) -> Self {
+        assert!(!points.is_empty(), "Cannot compute the bounds of no points");
+        let mut min = points[0];
+        let mut max = points[0];
+        for p in &points[1..] {
+            min = min.min(*p);
+            max = max.max(*p);
+        }
+        Self { min, max }
+    }
+
+    /// The width of the box.
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    /// The height of the box.
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    /// The centre of the box.
+    pub fn center(&self) -> DVec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns a copy of this box grown by `margin` in every direction.
+    pub fn expand(&self, margin: f64) -> Self {
+        Self {
+            min: self.min - DVec2::splat(margin),
+            max: self.max + DVec2::splat(margin),
+        }
+    }
+
+    /// Returns the bounds of this box after applying the given transform.
+    pub fn transform(&self, m: &DMat3) -> Self {
+        Self::from_points(&[
+            m.transform_point2(self.min),
+            m.transform_point2(dvec2(self.max.x, self.min.y)),
+            m.transform_point2(self.max),
+            m.transform_point2(dvec2(self.min.x, self.max.y)),
+        ])
+    }
+
+    /// Whether the given point is inside the box (boundary included).
+    pub fn contains(&self, p: DVec2) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Whether this box and `other` have at least one point in common.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+}
+
+/// The geometric measures of a tile.
+///
+/// See [`IsohedralTiling::measures`] and [`IsohedralTiling::measures_with_edges`].
+///
+/// [`IsohedralTiling::measures`]: crate::IsohedralTiling::measures
+/// [`IsohedralTiling::measures_with_edges`]: crate::IsohedralTiling::measures_with_edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measures {
+    /// The (unsigned) area of the tile.
+    pub area: f64,
+    /// The length of the boundary of the tile.
+    pub perimeter: f64,
+    /// The centre of mass of the tile.
+    pub centroid: DVec2,
+    /// The axis-aligned bounds of the tile.
+    pub bounds: BoundingBox,
+}
+
+impl Measures {
+    /// Computes all the measures of the given polygon.
+    pub fn of_polygon(poly: &[DVec2]) -> Self {
+        Self {
+            area: area(poly),
+            perimeter: perimeter(poly),
+            centroid: centroid(poly),
+            bounds: BoundingBox::from_points(poly),
+        }
+    }
+}
+
+/// The signed area of a polygon: positive if its points are in counter-clockwise order, negative
+/// otherwise.
+pub fn signed_area(poly: &[DVec2]) -> f64 {
+    let n = poly.len();
+    let mut total = 0.0;
+    for idx in 0..n {
+        let p = poly[idx];
+        let q = poly[(idx + 1) % n];
+        total += p.perp_dot(q);
+    }
+    total * 0.5
+}
+
+/// The area of a polygon, regardless of its orientation.
+pub fn area(poly: &[DVec2]) -> f64 {
+    signed_area(poly).abs()
+}
+
+/// The length of the boundary of a polygon.
+pub fn perimeter(poly: &[DVec2]) -> f64 {
+    let n = poly.len();
+    (0..n)
+        .map(|idx| poly[idx].distance(poly[(idx + 1) % n]))
+        .sum()
+}
+
+/// The centre of mass of a polygon.
+///
+/// Degenerate polygons (with an area of zero) fall back to the average of their points.
+pub fn centroid(poly: &[DVec2]) -> DVec2 {
+    let n = poly.len();
+    let a = signed_area(poly);
+    if a.abs() < 1e-12 {
+        return poly.iter().fold(DVec2::ZERO, |acc, p| acc + *p) / n as f64;
+    }
+
+    let mut c = DVec2::ZERO;
+    for idx in 0..n {
+        let p = poly[idx];
+        let q = poly[(idx + 1) % n];
+        let cross = p.perp_dot(q);
+        c += dvec2((p.x + q.x) * cross, (p.y + q.y) * cross);
+    }
+    c / (6.0 * a)
+}
+
+/// Whether the point `p` lies inside the polygon, using the even-odd rule.
+///
+/// Points lying exactly on the boundary may be reported as either inside or outside; use
+/// [`distance_to_boundary`] if that matters.
+pub fn contains_point(poly: &[DVec2], p: DVec2) -> bool {
+    let n = poly.len();
+    let mut inside = false;
+    for idx in 0..n {
+        let a = poly[idx];
+        let b = poly[(idx + 1) % n];
+        if (a.y > p.y) != (b.y > p.y) {
+            let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if p.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// The distance between the point `p` and the segment `[a, b]`.
+pub fn distance_to_segment(p: DVec2, a: DVec2, b: DVec2) -> f64 {
+    let ab = b - a;
+    let len2 = ab.length_squared();
+    if len2 == 0.0 {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / len2).clamp(0.0, 1.0);
+    p.distance(a + t * ab)
+}
+
+/// The distance between the point `p` and the boundary of the polygon.
+pub fn distance_to_boundary(poly: &[DVec2], p: DVec2) -> f64 {
+    let n = poly.len();
+    (0..n)
+        .map(|idx| distance_to_segment(p, poly[idx], poly[(idx + 1) % n]))
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Clips a polygon by a convex polygon, keeping the part of `poly` inside `convex`.
+///
+/// `poly` doesn't need to be convex, but if the result has several parts, they are joined by
+/// zero-width bridges along the boundary of `convex`. Both polygons may have any orientation, and
+/// the result has the orientation of `poly`. Returns an empty polygon if they don't overlap.
+pub fn clip_convex(poly: &[DVec2], convex: &[DVec2]) -> Vec<DVec2> {
+    let orientation = signed_area(convex).signum();
+    let n = convex.len();
+    let mut result = poly.to_vec();
+    for idx in 0..n {
+        let a = convex[idx];
+        let b = convex[(idx + 1) % n];
+        let inside = |p: DVec2| (b - a).perp_dot(p - a) * orientation >= 0.0;
+        let input = std::mem::take(&mut result);
+        for (i, &p) in input.iter().enumerate() {
+            let q = input[(i + 1) % input.len()];
+            if inside(p) {
+                result.push(p);
+            }
+            if inside(p) != inside(q) {
+                let dp = (b - a).perp_dot(p - a);
+                let dq = (b - a).perp_dot(q - a);
+                result.push(p.lerp(q, dp / (dp - dq)));
+            }
+        }
+    }
+
+    result
+}
+
+/// Whether the segments `[a, b]` and `[c, d]` cross each other at a single point which is not an
+/// end point of either segment (up to `eps`).
+pub(crate) fn segments_cross(a: DVec2, b: DVec2, c: DVec2, d: DVec2, eps: f64) -> bool {
+    let side = |p: DVec2, q: DVec2, r: DVec2| {
+        let pq = q - p;
+        let len = pq.length();
+        if len == 0.0 {
+            0.0
+        } else {
+            pq.perp_dot(r - p) / len
+        }
+    };
+    let d1 = side(a, b, c);
+    let d2 = side(a, b, d);
+    let d3 = side(c, d, a);
+    let d4 = side(c, d, b);
+
+    ((d1 > eps && d2 < -eps) || (d1 < -eps && d2 > eps))
+        && ((d3 > eps && d4 < -eps) || (d3 < -eps && d4 > eps))
+}
+
+/// Whether the polygon is simple, i.e. its boundary doesn't touch or cross itself and it doesn't
+/// have any zero-length edges.
+pub fn is_simple(poly: &[DVec2]) -> bool {
+    let n = poly.len();
+    if n < 3 {
+        return false;
+    }
+    let bounds = BoundingBox::from_points(poly);
+    let eps = 1e-9 * bounds.width().max(bounds.height()).max(1.0);
+
+    for i in 0..n {
+        let a = poly[i];
+        let b = poly[(i + 1) % n];
+        if a.distance(b) <= eps {
+            return false;
+        }
+        for j in (i + 1)..n {
+            let c = poly[j];
+            let d = poly[(j + 1) % n];
+            let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+            if adjacent {
+                // Consecutive edges only share their common end point, so they can't fold back
+                // onto each other
+                let (p, q, r) = if j == i + 1 { (a, b, d) } else { (c, a, b) };
+                if (q - p).perp_dot(r - q).abs() <= eps * (q - p).length()
+                    && (q - p).dot(r - q) < 0.0
+                {
+                    return false;
+                }
+            } else if segments_cross(a, b, c, d, eps)
+                || distance_to_segment(a, c, d) <= eps
+                || distance_to_segment(b, c, d) <= eps
+                || distance_to_segment(c, a, b) <= eps
+                || distance_to_segment(d, a, b) <= eps
+            {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Resamples a closed polygon into `n` points, evenly spaced along its boundary and starting at
+/// its first point.
+pub fn resample(poly: &[DVec2], n: usize) -> Vec<DVec2> {
+    let len = poly.len();
+    let total = perimeter(poly);
+    if total == 0.0 {
+        return vec![poly[0]; n];
+    }
+
+    let step = total / n as f64;
+    let mut points = Vec::with_capacity(n);
+    let mut edge = 0;
+    // Distance along the boundary at the start of the current edge
+    let mut start = 0.0;
+    for idx in 0..n {
+        let d = idx as f64 * step;
+        let mut edge_len = poly[edge].distance(poly[(edge + 1) % len]);
+        while start + edge_len < d && edge < len - 1 {
+            start += edge_len;
+            edge += 1;
+            edge_len = poly[edge].distance(poly[(edge + 1) % len]);
+        }
+        let t = if edge_len > 0.0 {
+            ((d - start) / edge_len).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        points.push(poly[edge].lerp(poly[(edge + 1) % len], t));
+    }
+    points
+}
+
+/// The number of points used to compare shapes in [`shape_distance`].
+const SHAPE_SAMPLES: usize = 64;
+
+/// Measures how different two closed shapes are, regardless of their position, size, orientation,
+/// starting point and direction of traversal.
+///
+/// Both shapes are resampled, centred on the origin and scaled to a unit RMS radius, and the
+/// result is the RMS distance between corresponding points after finding the best rotation and
+/// correspondence between the two. It is `0` for identical shapes and grows as they differ.
+pub fn shape_distance(a: &[DVec2], b: &[DVec2]) -> f64 {
+    let a = normalize_shape(&resample(a, SHAPE_SAMPLES));
+    let mut b = normalize_shape(&resample(b, SHAPE_SAMPLES));
+    let n = SHAPE_SAMPLES;
+
+    let mut best = f64::INFINITY;
+    for _ in 0..2 {
+        for shift in 0..n {
+            // Optimal rotation for this correspondence (orthogonal Procrustes)
+            let mut dot = 0.0;
+            let mut cross = 0.0;
+            for (idx, p) in a.iter().enumerate() {
+                let q = b[(idx + shift) % n];
+                dot += p.dot(q);
+                cross += p.perp_dot(q);
+            }
+            // Both shapes have a unit RMS radius
+            let residual = 2.0 * n as f64 - 2.0 * dot.hypot(cross);
+            best = best.min(residual);
+        }
+        b.reverse();
+    }
+
+    (best.max(0.0) / n as f64).sqrt()
+}
+
+/// Centres the points on the origin and scales them to a unit RMS radius.
+fn normalize_shape(points: &[DVec2]) -> Vec<DVec2> {
+    let n = points.len() as f64;
+    let center = points.iter().fold(DVec2::ZERO, |acc, p| acc + *p) / n;
+    let radius = (points
+        .iter()
+        .map(|p| p.distance_squared(center))
+        .sum::<f64>()
+        / n)
+        .sqrt();
+    let scale = if radius > 0.0 { 1.0 / radius } else { 1.0 };
+    points.iter().map(|p| (*p - center) * scale).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec2, DMat3};
+
+    use super::*;
+
+    #[test]
+    fn measures_of_known_shapes() {
+        let rectangle = [
+            dvec2(1.0, 2.0),
+            dvec2(4.0, 2.0),
+            dvec2(4.0, 4.0),
+            dvec2(1.0, 4.0),
+        ];
+        let m = Measures::of_polygon(&rectangle);
+        assert!((m.area - 6.0).abs() < 1e-12);
+        assert!((m.perimeter - 10.0).abs() < 1e-12);
+        assert!(m.centroid.abs_diff_eq(dvec2(2.5, 3.0), 1e-12));
+        assert_eq!(m.bounds.min, dvec2(1.0, 2.0));
+        assert_eq!(m.bounds.max, dvec2(4.0, 4.0));
+
+        // A clockwise right triangle still has a positive area
+        let triangle = [dvec2(0.0, 0.0), dvec2(0.0, 4.0), dvec2(3.0, 0.0)];
+        let m = Measures::of_polygon(&triangle);
+        assert!(signed_area(&triangle) < 0.0);
+        assert!((m.area - 6.0).abs() < 1e-12);
+        assert!((m.perimeter - 12.0).abs() < 1e-12);
+        assert!(m.centroid.abs_diff_eq(dvec2(1.0, 4.0 / 3.0), 1e-12));
+        assert_eq!(m.bounds.min, dvec2(0.0, 0.0));
+        assert_eq!(m.bounds.max, dvec2(3.0, 4.0));
+    }
+
+    #[test]
+    fn shape_distance_ignores_similarities() {
+        let square = [
+            dvec2(0.0, 0.0),
+            dvec2(1.0, 0.0),
+            dvec2(1.0, 1.0),
+            dvec2(0.0, 1.0),
+        ];
+        let m = DMat3::from_scale_angle_translation(dvec2(3.0, 3.0), 0.3, dvec2(5.0, -2.0));
+        let moved: Vec<_> = square
+            .iter()
+            .rev()
+            .map(|p| m.transform_point2(*p))
+            .collect();
+        assert!(shape_distance(&square, &moved) < 1e-2);
+
+        let triangle = [dvec2(0.0, 0.0), dvec2(1.0, 0.0), dvec2(0.5, 1.0)];
+        assert!(shape_distance(&square, &triangle) > 0.1);
+    }
+}
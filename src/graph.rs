@@ -0,0 +1,263 @@
+//! The adjacency graph of a finite patch of tiles, also known as its dual graph.
+//!
+//! See [`TileGraph`].
+use std::{collections::HashMap, fmt::Write};
+
+use glam::DVec2;
+
+use crate::{
+    adjacency::TileCoord,
+    geometry::{self, BoundingBox},
+    IsohedralTiling,
+};
+
+/// A link between two tiles sharing an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Link {
+    /// The indices of the two tiles in the graph.
+    pub tiles: (usize, usize),
+    /// The index of the shared edge in each of the two tiles.
+    pub edges: (usize, usize),
+    /// The id of the edge shape of the shared edge, which is the same on both sides.
+    pub shape_id: usize,
+}
+
+/// A graph whose nodes are the tiles of a finite patch, with a [`Link`] between each pair of
+/// tiles sharing an edge.
+///
+/// The links come from the [`Adjacency`](crate::adjacency::Adjacency) table of the tiling type
+/// (see [`IsohedralTiling::adjacency`]), which matches vertex positions within a small tolerance,
+/// but only once, for the default parameters. The graph itself never compares positions, so the
+/// links don't depend on the current parameters or on rounding errors far from the origin.
+#[derive(Debug, Clone)]
+pub struct TileGraph {
+    nodes: Vec<TileCoord>,
+    positions: Vec<DVec2>,
+    index: HashMap<TileCoord, usize>,
+    links: Vec<Link>,
+    /// The indices of the links of each node
+    node_links: Vec<Vec<usize>>,
+}
+
+impl TileGraph {
+    /// Builds the graph of the given tiles of `tiling`. Duplicate tiles are ignored.
+    pub fn new<I: IntoIterator<Item = TileCoord>>(tiling: &IsohedralTiling, tiles: I) -> Self {
+        let centre = geometry::centroid(tiling.vertices());
+        let mut graph = Self {
+            nodes: vec![],
+            positions: vec![],
+            index: HashMap::new(),
+            links: vec![],
+            node_links: vec![],
+        };
+        for tile in tiles {
+            if !graph.index.contains_key(&tile) {
+                graph.index.insert(tile, graph.nodes.len());
+                graph.nodes.push(tile);
+                let m = tiling.tile_transform(tile.t1, tile.t2, tile.aspect);
+                graph.positions.push(m.transform_point2(centre));
+                graph.node_links.push(vec![]);
+            }
+        }
+
+        let adjacency = tiling.adjacency();
+        for (a, &tile) in graph.nodes.iter().enumerate() {
+            for edge in 0..adjacency.num_edges() {
+                let (other, back) = adjacency.across(tile, edge);
+                match graph.index.get(&other) {
+                    // Only add each link once, from the side with the smallest (node, edge)
+                    Some(&b) if (a, edge) < (b, back) => {
+                        graph.node_links[a].push(graph.links.len());
+                        graph.node_links[b].push(graph.links.len());
+                        graph.links.push(Link {
+                            tiles: (a, b),
+                            edges: (edge, back),
+                            shape_id: tiling.ttd.edge_shape_ids[edge],
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// The number of tiles in the graph.
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The tiles of the graph, in the order of their indices.
+    pub fn nodes(&self) -> &[TileCoord] {
+        &self.nodes
+    }
+
+    /// The centre of each tile, in the order of their indices.
+    pub fn positions(&self) -> &[DVec2] {
+        &self.positions
+    }
+
+    /// All the links of the graph.
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Returns the index of a tile in the graph, or `None` if it isn't part of the graph.
+    pub fn node_index(&self, tile: TileCoord) -> Option<usize> {
+        self.index.get(&tile).copied()
+    }
+
+    /// Iterates over the links of the node with index `node`.
+    pub fn node_links(&self, node: usize) -> impl Iterator<Item = &Link> + '_ {
+        self.node_links[node].iter().map(move |&l| &self.links[l])
+    }
+
+    /// Iterates over the indices of the neighbours of the node with index `node`.
+    pub fn neighbours(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.node_links(node).map(move |link| {
+            if link.tiles.0 == node {
+                link.tiles.1
+            } else {
+                link.tiles.0
+            }
+        })
+    }
+
+    /// Exports the graph in the DOT format of Graphviz.
+    ///
+    /// Nodes are named `n<index>`, labelled with their coordinates, and pinned to the centre of
+    /// their tile (for layout engines such as `neato`). Links are labelled with their edge shape
+    /// id.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph tiling {\n");
+        // Writing to a `String` can't fail
+        for (idx, (tile, pos)) in self.nodes.iter().zip(&self.positions).enumerate() {
+            let _ = writeln!(
+                dot,
+                "  n{} [label=\"{},{},{}\", pos=\"{:.5},{:.5}!\"];",
+                idx, tile.t1, tile.t2, tile.aspect, pos.x, pos.y
+            );
+        }
+        for link in &self.links {
+            let _ = writeln!(
+                dot,
+                "  n{} -- n{} [label=\"{}\"];",
+                link.tiles.0, link.tiles.1, link.shape_id
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the graph in the GraphML format.
+    ///
+    /// Nodes have the attributes `t1`, `t2`, `aspect`, `x` and `y` (the centre of their tile),
+    /// and links have the attributes `shape_id`, `edge_source` and `edge_target` (the index of
+    /// the shared edge in each tile).
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        );
+        let keys = [
+            ("t1", "node", "int"),
+            ("t2", "node", "int"),
+            ("aspect", "node", "int"),
+            ("x", "node", "double"),
+            ("y", "node", "double"),
+            ("shape_id", "edge", "int"),
+            ("edge_source", "edge", "int"),
+            ("edge_target", "edge", "int"),
+        ];
+        for (name, domain, ty) in keys {
+            let _ = writeln!(
+                xml,
+                "  <key id=\"{0}\" for=\"{1}\" attr.name=\"{0}\" attr.type=\"{2}\"/>",
+                name, domain, ty
+            );
+        }
+
+        xml.push_str("  <graph id=\"tiling\" edgedefault=\"undirected\">\n");
+        for (idx, (tile, pos)) in self.nodes.iter().zip(&self.positions).enumerate() {
+            let _ = writeln!(
+                xml,
+                "    <node id=\"n{}\"><data key=\"t1\">{}</data><data key=\"t2\">{}</data>\
+                 <data key=\"aspect\">{}</data><data key=\"x\">{}</data><data key=\"y\">{}</data>\
+                 </node>",
+                idx, tile.t1, tile.t2, tile.aspect, pos.x, pos.y
+            );
+        }
+        for link in &self.links {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"shape_id\">{}</data>\
+                 <data key=\"edge_source\">{}</data><data key=\"edge_target\">{}</data></edge>",
+                link.tiles.0, link.tiles.1, link.shape_id, link.edges.0, link.edges.1
+            );
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+impl IsohedralTiling {
+    /// Builds the adjacency graph of the tiles covering `region`, as returned by
+    /// [`fill_region`](IsohedralTiling::fill_region).
+    pub fn tile_graph(&self, region: &BoundingBox) -> TileGraph {
+        let tiles = self
+            .fill_region(region.min.x, region.min.y, region.max.x, region.max.y)
+            .iter()
+            .map(|tile| TileCoord::new(tile.t1, tile.t2, tile.aspect))
+            .collect::<Vec<_>>();
+        TileGraph::new(self, tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{adjacency::TileCoord, geometry::BoundingBox, get_tiling_type, IsohedralTiling};
+
+    #[test]
+    fn graph_links_neighbours() {
+        let tiling = IsohedralTiling::new(get_tiling_type(6));
+        let region = BoundingBox {
+            min: dvec2(-3.0, -3.0),
+            max: dvec2(3.0, 3.0),
+        };
+        let graph = tiling.tile_graph(&region);
+        let n = tiling.num_vertices();
+        assert!(graph.num_nodes() > 10);
+
+        let mut full = 0;
+        for node in 0..graph.num_nodes() {
+            let degree = graph.neighbours(node).count();
+            assert!(degree <= n);
+            if degree == n {
+                full += 1;
+            }
+            for other in graph.neighbours(node) {
+                assert!(graph.neighbours(other).any(|o| o == node));
+                // Neighbours are close to each other
+                let d = graph.positions()[node].distance(graph.positions()[other]);
+                assert!(d < 2.0, "{}", d);
+            }
+        }
+        assert!(full > 0);
+        for link in graph.links() {
+            let shape = |edge| tiling.shapes().nth(edge).unwrap().id();
+            assert_eq!(shape(link.edges.0), link.shape_id);
+            assert_eq!(shape(link.edges.1), link.shape_id);
+        }
+
+        let origin = graph.node_index(TileCoord::new(0, 0, 0)).unwrap();
+        assert_eq!(graph.nodes()[origin], TileCoord::new(0, 0, 0));
+        let dot = graph.to_dot();
+        assert_eq!(dot.matches(" -- ").count(), graph.links().len());
+        let graphml = graph.to_graphml();
+        assert_eq!(graphml.matches("<node ").count(), graph.num_nodes());
+        assert_eq!(graphml.matches("<edge ").count(), graph.links().len());
+    }
+}
@@ -0,0 +1,143 @@
+//! A minimal representation of colours and RGB images.
+use std::fmt;
+
+use glam::{DMat3, DVec2};
+
+/// A colour with 8-bit red, green and blue components.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    /// The red component.
+    pub r: u8,
+    /// The green component.
+    pub g: u8,
+    /// The blue component.
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Creates a new colour from its components.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parses a colour in the CSS hexadecimal notation `#rrggbb`. Returns `None` if the string
+    /// isn't a valid colour.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#')?;
+        if digits.len() != 6 || !digits.is_ascii() {
+            return None;
+        }
+        let component = |idx: usize| u8::from_str_radix(&digits[idx..idx + 2], 16).ok();
+        Some(Self::new(component(0)?, component(2)?, component(4)?))
+    }
+
+    /// Interpolates linearly between this colour and `other`, where `t = 0` gives this colour
+    /// and `t = 1` gives `other`.
+    pub fn lerp(self, other: Rgb, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Self::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+}
+
+/// Formats the colour in the CSS hexadecimal notation `#rrggbb`.
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// An image made of RGB pixels, stored row by row starting from the top-left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl RgbImage {
+    /// Creates an image from its pixels, stored row by row.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of pixels isn't `width * height`.
+    pub fn new(width: usize, height: usize, pixels: Vec<Rgb>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "The number of pixels doesn't match the size of the image"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Creates an image by calling `f` with the coordinates `(x, y)` of each pixel.
+    pub fn from_fn<F: FnMut(usize, usize) -> Rgb>(width: usize, height: usize, mut f: F) -> Self {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    /// Creates an image from raw bytes, with three bytes (red, green and blue) per pixel, stored
+    /// row by row. This is the layout used by most image libraries. Returns `None` if the number
+    /// of bytes doesn't match the size of the image.
+    pub fn from_raw(width: usize, height: usize, data: &[u8]) -> Option<Self> {
+        if data.len() != width * height * 3 {
+            return None;
+        }
+        let pixels = data
+            .chunks_exact(3)
+            .map(|p| Rgb::new(p[0], p[1], p[2]))
+            .collect();
+        Some(Self::new(width, height, pixels))
+    }
+
+    /// The width of the image, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the image, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixels of the image, stored row by row.
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    /// Returns the pixel in column `x` and row `y`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the coordinates are outside the image.
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        assert!(x < self.width && y < self.height, "Pixel out of bounds");
+        self.pixels[y * self.width + x]
+    }
+
+    /// Returns the pixel covering the point `p`, where `placement` maps pixel coordinates (with
+    /// `(0, 0)` at the top-left corner of the image and `(width, height)` at its bottom-right
+    /// corner) to world coordinates. Points outside the image use the closest pixel on its border.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is empty.
+    pub fn sample(&self, placement: &DMat3, p: DVec2) -> Rgb {
+        assert!(!self.pixels.is_empty(), "Cannot sample an empty image");
+        let q = placement.inverse().transform_point2(p);
+        let x = (q.x.floor().max(0.0) as usize).min(self.width - 1);
+        let y = (q.y.floor().max(0.0) as usize).min(self.height - 1);
+        self.pixel(x, y)
+    }
+}
@@ -137,11 +137,16 @@ impl<'tiling> Iterator for TilingShapePartIterator<'tiling> {
     }
 }
 
-#[derive(Debug)]
+/// A tile returned when iterating over [`fill_region`](IsohedralTiling::fill_region).
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct FillRegionStep {
+    /// The number of copies of the first translation vector.
     pub t1: isize,
+    /// The number of copies of the second translation vector.
     pub t2: isize,
+    /// The aspect of the tile.
     pub aspect: usize,
+    /// The transform mapping the prototile onto this tile.
     pub transform: DMat3,
 }
 
@@ -235,16 +240,9 @@ impl<'tiling> FillRegionIterator<'tiling> {
     }
 
     fn transform(&self) -> DMat3 {
-        let mut m = *self.algo.tiling.aspect_transform(self.asp);
-        let t1 = self.algo.tiling.t1();
-        let t2 = self.algo.tiling.t2();
-
-        let x = self.x as i64 as f64;
-        let y = self.y as i64 as f64;
-        m.col_mut(2)[0] += x * t1.x + y * t2.x;
-        m.col_mut(2)[1] += x * t1.y + y * t2.y;
-
-        m
+        self.algo
+            .tiling
+            .tile_transform(self.x as isize, self.y as isize, self.asp)
     }
 }
 
@@ -1,20 +1,47 @@
-//!
+//! A library for representing, manipulating, and drawing isohedral tilings on the plane.
 #![warn(missing_docs, missing_debug_implementations)]
 use glam::{dvec2, DMat3, DVec2};
 
+pub mod adjacency;
+pub mod automaton;
+pub mod colouring;
+pub mod corona;
 pub mod data;
+pub mod domain;
+pub mod escher;
+pub mod geometry;
+pub mod graph;
+pub mod image;
 mod iterators;
+pub mod metamorphosis;
+pub mod morph;
+pub mod mosaic;
+pub mod optimize;
+pub mod palette;
+pub mod params;
+pub mod path;
+pub mod supercell;
+pub mod svg;
+pub mod symmetry;
+#[cfg(test)]
+mod test_utils;
+pub mod tile_id;
+pub mod torus;
 mod utils;
+pub mod verify;
+pub mod vertex;
 
 use data::{tiling_type_data, TilingTypeData};
+use geometry::{BoundingBox, Measures};
 use iterators::{FillAlgorithm, TilingShapeIterator, TilingShapePartIterator};
 use utils::{fill_matrix, fill_vector, r#match};
 
 pub use data::get_tiling_type;
+pub use iterators::FillRegionStep;
 
 /// One of the 93 isohedral tiling types. Can be used to initialise or reset an [`IsohedralTiling`]
 /// instance.
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TilingType(usize);
 
 impl std::fmt::Display for TilingType {
@@ -37,7 +64,7 @@ pub enum EdgeShape {
 }
 
 /// Represents a particular isohedral tiling type.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct IsohedralTiling {
     tiling_type: TilingType,
     num_params: usize,
@@ -74,7 +101,7 @@ impl IsohedralTiling {
         self.recompute();
     }
 
-    /// # Accessors
+    // # Accessors
 
     /// The tiling type described by this instance.
     pub fn tiling_type(&self) -> TilingType {
@@ -133,7 +160,7 @@ impl IsohedralTiling {
     ///
     /// The return value can be 0, 1, 2, representing one of 3 possible colours. The parameters `t1`,
     /// `t2`, and `aspect` can be obtained while iterating over the tiles of a region. See
-    /// [`iterators::FillRegionIterator`].
+    /// [`FillRegionStep`].
     pub fn colour(&self, t1: isize, t2: isize, aspect: usize) -> u8 {
         let nc = self.ttd.colouring[18] as isize;
 
@@ -158,6 +185,21 @@ impl IsohedralTiling {
         col
     }
 
+    /// Returns the transform mapping the prototile onto the tile with the given aspect, translated
+    /// by `t1` and `t2` copies of the translation vectors.
+    ///
+    /// This is the same transform as the one returned for each step of [`fill_region`].
+    ///
+    /// [`fill_region`]: IsohedralTiling::fill_region
+    pub fn tile_transform(&self, t1: isize, t2: isize, aspect: usize) -> DMat3 {
+        let mut m = self.aspects[aspect];
+        let offset = t1 as f64 * self.t1 + t2 as f64 * self.t2;
+        m.col_mut(2)[0] += offset.x;
+        m.col_mut(2)[1] += offset.y;
+
+        m
+    }
+
     /// The first translation vector.
     pub fn t1(&self) -> &DVec2 {
         &self.t1
@@ -168,10 +210,10 @@ impl IsohedralTiling {
         &self.t2
     }
 
-    /// # Iterators
+    // # Iterators
 
     /// Iterate over all the edge shapes of the prototile.
-    pub fn shapes(&self) -> TilingShapeIterator {
+    pub fn shapes(&self) -> TilingShapeIterator<'_> {
         TilingShapeIterator {
             idx: 0,
             tiling: self,
@@ -179,7 +221,7 @@ impl IsohedralTiling {
     }
 
     /// Iterate over all the shape parts of the prototile.
-    pub fn parts(&self) -> TilingShapePartIterator {
+    pub fn parts(&self) -> TilingShapePartIterator<'_> {
         TilingShapePartIterator {
             idx: 0,
             tiling: self,
@@ -224,6 +266,176 @@ impl IsohedralTiling {
         &self.verts[0..self.num_vertices()]
     }
 
+    /// Returns the outline of the prototile, using the given edge curves.
+    ///
+    /// `edges` must contain one curve for each edge shape (see [`num_edge_shapes`]), indexed by
+    /// shape id. Each curve is a polyline of at least 2 points going from `(0, 0)` to `(1, 0)`
+    /// which is mapped onto the prototile's edges using the transforms returned by [`parts`], so
+    /// `U` and `S` edges only need to describe the first half of the edge.
+    ///
+    /// The returned polygon is closed (its last point connects back to the first one), and its
+    /// first point is the first vertex of the prototile.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `edges` has fewer curves than there are edge shapes, or if one of
+    /// the curves has fewer than 2 points.
+    ///
+    /// [`num_edge_shapes`]: IsohedralTiling::num_edge_shapes
+    /// [`parts`]: IsohedralTiling::parts
+    pub fn outline(&self, edges: &[Vec<DVec2>]) -> Vec<DVec2> {
+        let mut points = vec![];
+        for path in self.edge_paths(edges) {
+            points.extend_from_slice(&path[..path.len() - 1]);
+        }
+
+        points
+    }
+
+    /// Returns, for each edge of the prototile, the polyline going from vertex `i` to vertex
+    /// `i + 1` when drawn with the given edge curves.
+    ///
+    /// See [`outline`] for the format of `edges`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `edges` has fewer curves than there are edge shapes, or if one of
+    /// the curves has fewer than 2 points.
+    ///
+    /// [`outline`]: IsohedralTiling::outline
+    pub fn edge_paths(&self, edges: &[Vec<DVec2>]) -> Vec<Vec<DVec2>> {
+        (0..self.num_vertices())
+            .map(|idx| {
+                let curve = &edges[self.ttd.edge_shape_ids[idx]];
+                assert!(curve.len() >= 2, "Edge curves need at least 2 points");
+                self.edge_path(idx, &self.edges[idx], curve)
+            })
+            .collect()
+    }
+
+    /// Maps `curve` onto edge `idx` using `transform`, which maps the unit segment onto the edge
+    /// in the same way as the transforms returned by [`shapes`]. `U` and `S` edges are split in
+    /// two halves like in [`parts`].
+    ///
+    /// [`shapes`]: IsohedralTiling::shapes
+    /// [`parts`]: IsohedralTiling::parts
+    pub(crate) fn edge_path(&self, idx: usize, transform: &DMat3, curve: &[DVec2]) -> Vec<DVec2> {
+        let shape = self.ttd.edge_shapes[self.ttd.edge_shape_ids[idx]];
+        let rev = self.reversals[idx];
+        let halves = match shape {
+            EdgeShape::J | EdgeShape::I => vec![(*transform, rev)],
+            EdgeShape::U | EdgeShape::S => {
+                let ms = if shape == EdgeShape::U {
+                    &utils::TSPI_U
+                } else {
+                    &utils::TSPI_S
+                };
+                let (first, second) = if rev { (ms[1], ms[0]) } else { (ms[0], ms[1]) };
+                vec![(*transform * first, false), (*transform * second, true)]
+            }
+        };
+
+        let start = if halves[0].1 {
+            curve[curve.len() - 1]
+        } else {
+            curve[0]
+        };
+        let mut path = vec![halves[0].0.transform_point2(start)];
+        for (t, reversed) in halves {
+            if reversed {
+                path.extend(
+                    curve[..curve.len() - 1]
+                        .iter()
+                        .rev()
+                        .map(|p| t.transform_point2(*p)),
+                );
+            } else {
+                path.extend(curve[1..].iter().map(|p| t.transform_point2(*p)));
+            }
+        }
+
+        path
+    }
+
+    /// The transform applied to the unit segment, after mapping it onto edge `idx`, to give it
+    /// the right orientation.
+    pub(crate) fn edge_orientation(&self, idx: usize) -> DMat3 {
+        let fl = self.ttd.edge_orientations[2 * idx];
+        let ro = self.ttd.edge_orientations[2 * idx + 1];
+        utils::M_ORIENTS[2 * (fl as usize) + (ro as usize)]
+    }
+
+    /// Computes the area, perimeter, centroid and bounds of the straight-edged prototile returned
+    /// by [`vertices`].
+    ///
+    /// [`vertices`]: IsohedralTiling::vertices
+    pub fn measures(&self) -> Measures {
+        Measures::of_polygon(self.vertices())
+    }
+
+    /// Computes the area, perimeter, centroid and bounds of the prototile drawn with the given
+    /// edge curves.
+    ///
+    /// See [`outline`] for the format of `edges`.
+    ///
+    /// [`outline`]: IsohedralTiling::outline
+    pub fn measures_with_edges(&self, edges: &[Vec<DVec2>]) -> Measures {
+        Measures::of_polygon(&self.outline(edges))
+    }
+
+    /// The area of the parallelogram spanned by the two translation vectors.
+    ///
+    /// Since each translational unit cell contains exactly one copy of each aspect, this is
+    /// always equal to the area of the prototile times [`num_aspects`].
+    ///
+    /// [`num_aspects`]: IsohedralTiling::num_aspects
+    pub fn unit_cell_area(&self) -> f64 {
+        self.t1.perp_dot(self.t2).abs()
+    }
+
+    /// Returns the lattice coordinates and aspect of all the tiles whose bounds intersect
+    /// `region`, where `bounds` are the bounds of the prototile.
+    ///
+    /// Unlike [`fill_region`], this is guaranteed to return every tile touching the region, but
+    /// is only meant for small regions around the origin.
+    ///
+    /// [`fill_region`]: IsohedralTiling::fill_region
+    pub(crate) fn tiles_in_bounds(
+        &self,
+        region: &BoundingBox,
+        bounds: &BoundingBox,
+    ) -> Vec<(isize, isize, usize)> {
+        let mut tiles = vec![];
+        let mut empty_rings = 0;
+        let mut ring: isize = 0;
+        // Grow square rings of lattice cells until two consecutive rings miss the region
+        while empty_rings < 2 {
+            let mut found = false;
+            for i in -ring..=ring {
+                for j in -ring..=ring {
+                    if i.abs() != ring && j.abs() != ring {
+                        continue;
+                    }
+                    for aspect in 0..self.num_aspects() {
+                        let m = self.tile_transform(i, j, aspect);
+                        if bounds.transform(&m).intersects(region) {
+                            tiles.push((i, j, aspect));
+                            found = true;
+                        }
+                    }
+                }
+            }
+            if found {
+                empty_rings = 0;
+            } else {
+                empty_rings += 1;
+            }
+            ring += 1;
+        }
+
+        tiles
+    }
+
     fn recompute(&mut self) {
         let ntv = self.ttd.num_vertices;
 
@@ -245,7 +457,7 @@ impl IsohedralTiling {
             let ro = self.ttd.edge_orientations[2 * idx + 1];
             self.reversals[idx] = fl != ro;
             self.edges[idx] = r#match(&self.verts[idx], &self.verts[(idx + 1) % ntv])
-                * utils::M_ORIENTS[2 * (fl as usize) + (ro as usize)];
+                * self.edge_orientation(idx);
         }
 
         // Recompute aspect xforms
@@ -293,4 +505,45 @@ mod tests {
         }
         println!("Got {} tiles", cnt);
     }
+
+    #[test]
+    fn measures() {
+        // The prototile of IH41 is the unit square
+        let squares = IsohedralTiling::new(TilingType(41));
+        let m = squares.measures();
+        assert!((m.area - 1.0).abs() < 1e-9);
+        assert!((m.perimeter - 4.0).abs() < 1e-9);
+        assert!(m.centroid.abs_diff_eq(dvec2(0.5, 0.5), 1e-9));
+        assert!(m.bounds.min.abs_diff_eq(dvec2(0.0, 0.0), 1e-9));
+        assert!(m.bounds.max.abs_diff_eq(dvec2(1.0, 1.0), 1e-9));
+
+        // Straight edge curves give the same measures
+        let straight = vec![vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)]; squares.num_edge_shapes()];
+        let curved = squares.measures_with_edges(&straight);
+        assert!((curved.area - m.area).abs() < 1e-12);
+        assert!((curved.perimeter - m.perimeter).abs() < 1e-12);
+        assert!(curved.centroid.abs_diff_eq(m.centroid, 1e-12));
+    }
+
+    #[test]
+    fn unit_cell_area() {
+        for n in 0..81 {
+            let tiling = IsohedralTiling::new(get_tiling_type(n));
+            let edges = test_utils::bumpy_edges(&tiling, dvec2(0.3, 0.2));
+
+            let expected = tiling.unit_cell_area() / tiling.num_aspects() as f64;
+            let straight = tiling.measures();
+            let curved = tiling.measures_with_edges(&edges);
+            assert!(
+                (straight.area - expected).abs() < 1e-9,
+                "{}",
+                tiling.tiling_type()
+            );
+            assert!(
+                (curved.area - expected).abs() < 1e-9,
+                "{}",
+                tiling.tiling_type()
+            );
+        }
+    }
 }
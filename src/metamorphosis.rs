@@ -0,0 +1,259 @@
+//! Spatially varying tilings, where the shape of the tiles changes gradually across the plane, as
+//! in M.C. Escher's *Metamorphosis* prints.
+//!
+//! The tiles are laid out following a fixed *base* design, and a design field gives the
+//! parameters and edge curves to use at each point of the plane. To keep shared edges consistent
+//! between neighbouring tiles, the field is never evaluated per tile:
+//! - each tiling vertex is placed where it would be in a tiling using the parameters sampled at
+//!   the vertex's position in the base tiling, relative to the lattice coordinates of that
+//!   position. Since all the tiles meeting at a vertex sample the field at the same point, they all
+//!   agree on its new position.
+//! - each edge uses the curves sampled at the midpoint of the edge in the base tiling, which is
+//!   also shared by the two tiles on either side of the edge.
+//!
+//! See [`Metamorphosis`].
+use glam::{dvec2, DMat2, DMat3, DVec2};
+
+use crate::{
+    geometry::BoundingBox,
+    morph::{Design, Morph},
+    utils::r#match,
+    IsohedralTiling,
+};
+
+/// A tile of a [`Metamorphosis`].
+#[derive(Debug, Clone)]
+pub struct MorphedTile {
+    /// The first lattice coordinate of the tile in the base tiling.
+    pub t1: isize,
+    /// The second lattice coordinate of the tile in the base tiling.
+    pub t2: isize,
+    /// The aspect of the tile.
+    pub aspect: usize,
+    /// The outline of the tile, in world coordinates.
+    pub outline: Vec<DVec2>,
+}
+
+/// A tiling whose design varies across the plane.
+///
+/// The field is a function returning the design to use at a given point of the plane. All the
+/// designs it returns must have the same tiling type as the base design, and the same number of
+/// edge curves.
+pub struct Metamorphosis<F> {
+    base: IsohedralTiling,
+    field: F,
+}
+
+impl<F> std::fmt::Debug for Metamorphosis<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Metamorphosis")
+            .field("base", &self.base)
+            .finish()
+    }
+}
+
+impl<F: Fn(DVec2) -> Design> Metamorphosis<F> {
+    /// Creates a new metamorphosis, laying out tiles following `base` and shaping them according
+    /// to `field`.
+    pub fn new(base: &Design, field: F) -> Self {
+        Self {
+            base: base.tiling(),
+            field,
+        }
+    }
+
+    /// Computes the outlines of the tiles covering the given region.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the field returns a design with a different tiling type than the
+    /// base design.
+    pub fn tiles(&self, region: &BoundingBox) -> Vec<MorphedTile> {
+        let base = &self.base;
+        let mut scratch = base.clone();
+        let n = base.num_vertices();
+        let lattice = DMat2::from_cols(base.t1, base.t2);
+        let inverse = lattice.inverse();
+
+        let mut tiles = vec![];
+        for tile in &base.fill_region(region.min.x, region.min.y, region.max.x, region.max.y) {
+            let base_verts: Vec<_> = base
+                .vertices()
+                .iter()
+                .map(|v| tile.transform.transform_point2(*v))
+                .collect();
+
+            // Move each vertex according to the parameters at its position. The translation vectors
+            // change with the parameters, so the vertex is anchored at the lattice coordinates of
+            // its base position rather than at the tile's own cell, otherwise the tiles would drift
+            // further away from their base tiles the further they are from the origin. They aren't
+            // rounded down to a cell, which would stretch the tiles crossing the border of a cell
+            // by the whole change of the translation vectors.
+            let verts: Vec<_> = (0..n)
+                .map(|idx| {
+                    let design = self.sample(base_verts[idx]);
+                    scratch.set_parameters(&design.parameters);
+                    let position = inverse * base_verts[idx];
+                    scratch
+                        .tile_transform(tile.t1, tile.t2, tile.aspect)
+                        .transform_point2(*scratch.vertex(idx))
+                        + lattice * position
+                        - DMat2::from_cols(scratch.t1, scratch.t2) * position
+                })
+                .collect();
+
+            // Reflected tiles need their edge curves flipped to stay consistent with their
+            // neighbours
+            let flip = if tile.transform.determinant() < 0.0 {
+                DMat3::from_scale(dvec2(1.0, -1.0))
+            } else {
+                DMat3::IDENTITY
+            };
+
+            let mut outline = vec![];
+            for idx in 0..n {
+                let p = verts[idx];
+                let q = verts[(idx + 1) % n];
+                let design = self.sample((base_verts[idx] + base_verts[(idx + 1) % n]) * 0.5);
+                let curve = &design.edges[base.ttd.edge_shape_ids[idx]];
+                let transform = r#match(&p, &q) * flip * base.edge_orientation(idx);
+                let path = base.edge_path(idx, &transform, curve);
+                outline.extend_from_slice(&path[..path.len() - 1]);
+            }
+
+            tiles.push(MorphedTile {
+                t1: tile.t1,
+                t2: tile.t2,
+                aspect: tile.aspect,
+                outline,
+            });
+        }
+
+        tiles
+    }
+
+    fn sample(&self, p: DVec2) -> Design {
+        let design = (self.field)(p);
+        assert_eq!(
+            design.tiling_type,
+            self.base.tiling_type(),
+            "The design field must always return the same tiling type as the base design"
+        );
+        design
+    }
+}
+
+/// A design field that morphs linearly from the first design of `morph` at point `start` to its
+/// last design at point `end`, e.g. from left to right. Points before `start` or after `end` use
+/// the first or last design respectively.
+pub fn linear_field(morph: Morph, start: DVec2, end: DVec2) -> impl Fn(DVec2) -> Design {
+    let dir = end - start;
+    let len2 = dir.length_squared();
+    move |p| {
+        let t = if len2 > 0.0 {
+            (p - start).dot(dir) / len2
+        } else {
+            0.0
+        };
+        morph.at(t.clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use glam::{dvec2, DVec2};
+
+    use crate::{
+        geometry::{self, BoundingBox},
+        get_tiling_type,
+        morph::{Design, Morph, MorphOptions},
+        test_utils, EdgeShape, IsohedralTiling,
+    };
+
+    use super::{linear_field, Metamorphosis, MorphedTile};
+
+    #[test]
+    fn morphed_tiles_fit_together() {
+        let mut tiling = IsohedralTiling::new(get_tiling_type(4));
+        let edges = test_utils::bumpy_edges(&tiling, dvec2(0.5, 0.0));
+        let from = Design::new(&tiling, &edges);
+        let mut params = from.parameters;
+        params[0] += 0.05;
+        tiling.set_parameters(&params);
+        let mut curvy = edges.clone();
+        for (idx, curve) in curvy.iter_mut().enumerate() {
+            if tiling.edge_shape(idx) != EdgeShape::I {
+                curve[1] = dvec2(0.4, 0.15);
+            }
+        }
+        let to = Design::new(&tiling, &curvy);
+
+        let morph = Morph::new(&from, &to, &MorphOptions::default()).unwrap();
+        let region = BoundingBox {
+            min: dvec2(-3.0, -2.0),
+            max: dvec2(3.0, 2.0),
+        };
+        let meta = Metamorphosis::new(
+            &from,
+            linear_field(morph, dvec2(-3.0, 0.0), dvec2(3.0, 0.0)),
+        );
+        let constant = Metamorphosis::new(&from, |_| from.clone());
+
+        // Count the segments that only belong to one tile: if the tiles fit together, only the
+        // ones on the boundary of the patch remain, exactly like with a constant design.
+        let unshared = |tiles: Vec<MorphedTile>| {
+            let key = |p: DVec2| ((p.x * 1e5).round() as i64, (p.y * 1e5).round() as i64);
+            let mut segments: HashMap<_, usize> = HashMap::new();
+            for tile in &tiles {
+                assert!(geometry::is_simple(&tile.outline));
+                let n = tile.outline.len();
+                for idx in 0..n {
+                    let (a, b) = (key(tile.outline[idx]), key(tile.outline[(idx + 1) % n]));
+                    *segments.entry((a.min(b), a.max(b))).or_default() += 1;
+                }
+            }
+            assert!(segments.values().all(|&count| count <= 2));
+            segments.values().filter(|&&count| count == 1).count()
+        };
+        assert_eq!(
+            unshared(meta.tiles(&region)),
+            unshared(constant.tiles(&region))
+        );
+    }
+
+    #[test]
+    fn morphed_tiles_stay_near_base_tiles() {
+        let mut tiling = IsohedralTiling::new(get_tiling_type(4));
+        let edges = test_utils::bumpy_edges(&tiling, dvec2(0.5, 0.0));
+        let base = tiling.clone();
+        let from = Design::new(&tiling, &edges);
+        let mut params = from.parameters;
+        params[0] += 0.3;
+        tiling.set_parameters(&params);
+        let to = Design::new(&tiling, &edges);
+        let meta = Metamorphosis::new(&from, |_| to.clone());
+
+        // Far from the origin, the tiles move as much as near it
+        let mut near = None;
+        for x in [0.0, 10.0, 30.0, 100.0] {
+            let region = BoundingBox {
+                min: dvec2(x - 3.0, -2.0),
+                max: dvec2(x + 3.0, 2.0),
+            };
+            let tiles = meta.tiles(&region);
+            assert!(!tiles.is_empty());
+            let furthest = tiles
+                .iter()
+                .map(|tile| {
+                    let m = base.tile_transform(tile.t1, tile.t2, tile.aspect);
+                    m.transform_point2(base.vertices()[0])
+                        .distance(tile.outline[0])
+                })
+                .fold(0.0, f64::max);
+            let near = *near.get_or_insert(furthest);
+            assert!(furthest <= near + 1e-6, "{}: {} > {}", x, furthest, near);
+        }
+    }
+}
@@ -0,0 +1,452 @@
+//! Smooth interpolation between two designs of the same tiling type.
+//!
+//! See [`Morph`].
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use glam::DVec2;
+
+use crate::{
+    geometry::BoundingBox,
+    palette::TileColouring,
+    svg::{render_svg, SvgOptions},
+    IsohedralTiling, TilingType,
+};
+
+/// A complete tile design: a tiling type, its parameters and its edge curves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Design {
+    /// The tiling type.
+    pub tiling_type: TilingType,
+    /// The parameters of the prototile.
+    pub parameters: [f64; 6],
+    /// The edge curves of the prototile, in the format described in
+    /// [`IsohedralTiling::outline`].
+    pub edges: Vec<Vec<DVec2>>,
+}
+
+impl Design {
+    /// Captures the current state of `tiling`, drawn with the given edge curves.
+    pub fn new(tiling: &IsohedralTiling, edges: &[Vec<DVec2>]) -> Self {
+        let mut parameters = [0.0; 6];
+        tiling.parameters(&mut parameters);
+        Self {
+            tiling_type: tiling.tiling_type(),
+            parameters,
+            edges: edges.to_vec(),
+        }
+    }
+
+    /// Creates a tiling set up with the type and parameters of this design.
+    pub fn tiling(&self) -> IsohedralTiling {
+        let mut tiling = IsohedralTiling::new(self.tiling_type);
+        tiling.set_parameters(&self.parameters);
+        tiling
+    }
+
+    /// Whether the prototile of this design is a simple polygon.
+    pub fn is_valid(&self) -> bool {
+        self.tiling().is_simple_with_edges(&self.edges)
+    }
+}
+
+/// Easing functions controlling the speed of a [`Morph`] over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slowly and accelerates.
+    EaseIn,
+    /// Starts fast and decelerates.
+    EaseOut,
+    /// Starts and ends slowly.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps a time `t` between `0` and `1` to an interpolation factor between `0` and `1`.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// The settings of a [`Morph`].
+#[derive(Debug, Clone, Copy)]
+pub struct MorphOptions {
+    /// The easing applied to the time.
+    pub easing: Easing,
+    /// Whether the morph must stay in the valid region of the parameter space, i.e. whether all
+    /// the intermediate prototiles must be simple polygons.
+    pub constrain: bool,
+    /// The number of intermediate designs checked when `constrain` is set. Only these designs
+    /// are checked, so a morph can still go through invalid designs between two samples.
+    pub samples: usize,
+}
+
+impl Default for MorphOptions {
+    fn default() -> Self {
+        Self {
+            easing: Easing::Linear,
+            constrain: true,
+            samples: 100,
+        }
+    }
+}
+
+/// The reasons why a [`Morph`] can't be created.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MorphError {
+    /// The two designs don't have the same tiling type.
+    DifferentTilingTypes(TilingType, TilingType),
+    /// The two designs don't have the same number of edge curves.
+    DifferentEdgeCount,
+    /// One of the two designs isn't valid.
+    InvalidDesign,
+    /// No path between the two designs staying in the valid region of the parameter space was
+    /// found.
+    LeavesValidRegion,
+}
+
+impl fmt::Display for MorphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MorphError::DifferentTilingTypes(a, b) => {
+                write!(f, "can't morph between tiling types {} and {}", a, b)
+            }
+            MorphError::DifferentEdgeCount => {
+                f.write_str("the designs don't have the same number of edge curves")
+            }
+            MorphError::InvalidDesign => f.write_str("one of the designs isn't valid"),
+            MorphError::LeavesValidRegion => {
+                f.write_str("no valid path was found between the two designs")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MorphError {}
+
+/// An animation between two designs of the same tiling type.
+///
+/// Parameters are interpolated linearly, and each edge curve is interpolated point by point after
+/// both versions have been resampled to the same points (by arc length), so that the end designs
+/// are reproduced exactly.
+///
+/// When constrained, if the straight path between the two designs leaves the valid region, the
+/// morph goes through the default parameters of the tiling type instead.
+#[derive(Debug, Clone)]
+pub struct Morph {
+    from: Design,
+    to: Design,
+    /// Intermediate parameters the morph goes through halfway, if any
+    via: Option<[f64; 6]>,
+    /// Both versions of each edge curve, resampled to the same number of points
+    edges: Vec<(Vec<DVec2>, Vec<DVec2>)>,
+    easing: Easing,
+}
+
+impl Morph {
+    /// Creates a new morph between the two designs.
+    pub fn new(from: &Design, to: &Design, options: &MorphOptions) -> Result<Self, MorphError> {
+        if from.tiling_type != to.tiling_type {
+            return Err(MorphError::DifferentTilingTypes(
+                from.tiling_type,
+                to.tiling_type,
+            ));
+        }
+        if from.edges.len() != to.edges.len() {
+            return Err(MorphError::DifferentEdgeCount);
+        }
+
+        let edges = from
+            .edges
+            .iter()
+            .zip(&to.edges)
+            .map(|(a, b)| resample_together(a, b))
+            .collect();
+        let mut morph = Self {
+            from: from.clone(),
+            to: to.clone(),
+            via: None,
+            edges,
+            easing: options.easing,
+        };
+
+        if options.constrain {
+            if !from.is_valid() || !to.is_valid() {
+                return Err(MorphError::InvalidDesign);
+            }
+            if !morph.stays_valid(options.samples) {
+                let tiling = IsohedralTiling::new(from.tiling_type);
+                let mut defaults = [0.0; 6];
+                tiling.parameters(&mut defaults);
+                morph.via = Some(defaults);
+                if !morph.stays_valid(options.samples) {
+                    return Err(MorphError::LeavesValidRegion);
+                }
+            }
+        }
+
+        Ok(morph)
+    }
+
+    /// Returns the design at time `t`, between `0` (the first design) and `1` (the second one).
+    pub fn at(&self, t: f64) -> Design {
+        let s = self.easing.apply(t);
+        let parameters = match self.via {
+            None => lerp_params(&self.from.parameters, &self.to.parameters, s),
+            Some(via) if s < 0.5 => lerp_params(&self.from.parameters, &via, 2.0 * s),
+            Some(via) => lerp_params(&via, &self.to.parameters, 2.0 * s - 1.0),
+        };
+        let edges = self
+            .edges
+            .iter()
+            .map(|(a, b)| a.iter().zip(b).map(|(p, q)| p.lerp(*q, s)).collect())
+            .collect();
+
+        Design {
+            tiling_type: self.from.tiling_type,
+            parameters,
+            edges,
+        }
+    }
+
+    /// Returns `n` evenly spaced designs, from the first design to the last one (both included).
+    pub fn frames(&self, n: usize) -> Vec<Design> {
+        match n {
+            0 => vec![],
+            1 => vec![self.at(0.0)],
+            _ => (0..n)
+                .map(|idx| self.at(idx as f64 / (n - 1) as f64))
+                .collect(),
+        }
+    }
+
+    /// Writes `n` frames of the morph as SVG files named `frame_0000.svg`, `frame_0001.svg`, etc.
+    /// in the directory `dir`, each drawing the tiles covering `region` filled according to
+    /// `colouring`. Returns the paths of the files written.
+    pub fn export_svg_frames<C: TileColouring + ?Sized>(
+        &self,
+        n: usize,
+        dir: &Path,
+        region: &BoundingBox,
+        colouring: &C,
+        options: &SvgOptions,
+    ) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        self.frames(n)
+            .iter()
+            .enumerate()
+            .map(|(idx, design)| {
+                let path = dir.join(format!("frame_{:04}.svg", idx));
+                fs::write(
+                    &path,
+                    render_svg(&design.tiling(), &design.edges, region, colouring, options),
+                )?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    fn stays_valid(&self, samples: usize) -> bool {
+        let mut tiling = IsohedralTiling::new(self.from.tiling_type);
+        (0..=samples).all(|idx| {
+            let design = self.at(idx as f64 / samples as f64);
+            tiling.set_parameters(&design.parameters);
+            tiling.is_simple_with_edges(&design.edges)
+        })
+    }
+}
+
+fn lerp_params(a: &[f64; 6], b: &[f64; 6], t: f64) -> [f64; 6] {
+    let mut params = [0.0; 6];
+    for (idx, p) in params.iter_mut().enumerate() {
+        *p = a[idx] + (b[idx] - a[idx]) * t;
+    }
+    params
+}
+
+/// Resamples two polylines at the union of their vertices' relative arc length positions, so that
+/// they have the same number of points while keeping their exact shapes.
+fn resample_together(a: &[DVec2], b: &[DVec2]) -> (Vec<DVec2>, Vec<DVec2>) {
+    if a.len() == b.len() {
+        return (a.to_vec(), b.to_vec());
+    }
+
+    let mut positions: Vec<f64> = arc_positions(a)
+        .into_iter()
+        .chain(arc_positions(b))
+        .collect();
+    positions.sort_by(|x, y| x.total_cmp(y));
+    positions.dedup_by(|x, y| (*x - *y).abs() < 1e-12);
+
+    (
+        positions.iter().map(|&s| sample_polyline(a, s)).collect(),
+        positions.iter().map(|&s| sample_polyline(b, s)).collect(),
+    )
+}
+
+/// The position of each vertex of the polyline along its length, between `0` and `1`.
+fn arc_positions(curve: &[DVec2]) -> Vec<f64> {
+    let mut positions = vec![0.0];
+    let mut total = 0.0;
+    for pair in curve.windows(2) {
+        total += pair[0].distance(pair[1]);
+        positions.push(total);
+    }
+    if total > 0.0 {
+        positions.iter_mut().for_each(|p| *p /= total);
+    } else {
+        // Degenerate curve: spread the vertices evenly
+        let n = (curve.len() - 1).max(1) as f64;
+        positions = (0..curve.len()).map(|idx| idx as f64 / n).collect();
+    }
+    positions
+}
+
+/// The point at relative arc length `s` along the polyline.
+fn sample_polyline(curve: &[DVec2], s: f64) -> DVec2 {
+    let positions = arc_positions(curve);
+    for idx in 1..curve.len() {
+        if s <= positions[idx] {
+            let span = positions[idx] - positions[idx - 1];
+            let t = if span > 0.0 {
+                (s - positions[idx - 1]) / span
+            } else {
+                0.0
+            };
+            return curve[idx - 1].lerp(curve[idx], t);
+        }
+    }
+    curve[curve.len() - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{
+        geometry::BoundingBox, get_tiling_type, palette::Builtin, svg::SvgOptions, IsohedralTiling,
+    };
+
+    use super::{Design, Morph, MorphError, MorphOptions};
+
+    #[test]
+    fn morph_reproduces_end_designs() {
+        let mut tiling = IsohedralTiling::new(get_tiling_type(0));
+        let straight = vec![vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)]; 3];
+        let from = Design::new(&tiling, &straight);
+
+        let mut params = from.parameters;
+        params[0] += 0.1;
+        tiling.set_parameters(&params);
+        let mut curvy = straight.clone();
+        curvy[1] = vec![
+            dvec2(0.0, 0.0),
+            dvec2(0.3, 0.1),
+            dvec2(0.6, -0.1),
+            dvec2(1.0, 0.0),
+        ];
+        let to = Design::new(&tiling, &curvy);
+
+        let morph = Morph::new(&from, &to, &MorphOptions::default()).unwrap();
+        let frames = morph.frames(5);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0].parameters, from.parameters);
+        assert_eq!(frames[4].parameters, to.parameters);
+        assert_eq!(frames[4].edges[1].len(), 4);
+        assert!(frames.iter().all(|d| d.is_valid()));
+
+        let other = Design::new(&IsohedralTiling::new(get_tiling_type(1)), &straight);
+        assert!(matches!(
+            Morph::new(&from, &other, &MorphOptions::default()),
+            Err(MorphError::DifferentTilingTypes(..))
+        ));
+    }
+
+    #[test]
+    fn constrained_morph_avoids_invalid_designs() {
+        let straight = vec![vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)]; 3];
+        let design = |parameters: [f64; 4]| {
+            let mut tiling = IsohedralTiling::new(get_tiling_type(0));
+            let mut params = [0.0; 6];
+            params[..4].copy_from_slice(&parameters);
+            tiling.set_parameters(&params);
+            Design::new(&tiling, &straight)
+        };
+        let free = MorphOptions {
+            constrain: false,
+            ..Default::default()
+        };
+
+        // The straight path goes through invalid designs, but the one through the default
+        // parameters doesn't
+        let (from, to) = (design([0.0, 1.0, 0.2, 0.3]), design([0.7, 0.0, 0.0, 0.6]));
+        assert!(from.is_valid() && to.is_valid());
+        let straight_path = Morph::new(&from, &to, &free).unwrap();
+        assert!(straight_path.frames(101).iter().any(|d| !d.is_valid()));
+        let morph = Morph::new(&from, &to, &MorphOptions::default()).unwrap();
+        let mut defaults = [0.0; 6];
+        IsohedralTiling::new(get_tiling_type(0)).parameters(&mut defaults);
+        assert_eq!(morph.at(0.5).parameters, defaults);
+        let frames = morph.frames(101);
+        assert!(frames.iter().all(|d| d.is_valid()));
+        assert_eq!(frames[0].parameters, from.parameters);
+        assert_eq!(frames[100].parameters, to.parameters);
+
+        // Neither path stays valid
+        let (from, to) = (design([0.5, 0.1, 0.5, 1.0]), design([-0.1, 0.0, -0.1, 0.0]));
+        assert!(from.is_valid() && to.is_valid());
+        assert_eq!(
+            Morph::new(&from, &to, &MorphOptions::default()).unwrap_err(),
+            MorphError::LeavesValidRegion
+        );
+        assert!(Morph::new(&from, &to, &free).is_ok());
+    }
+
+    #[test]
+    fn frames_are_exported_as_svg() {
+        let tiling = IsohedralTiling::new(get_tiling_type(0));
+        let straight = vec![vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)]; 3];
+        let from = Design::new(&tiling, &straight);
+        let mut to = from.clone();
+        to.parameters[0] += 0.1;
+        let morph = Morph::new(&from, &to, &MorphOptions::default()).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("tactile-frames-{}", std::process::id()));
+        let region = BoundingBox {
+            min: dvec2(-2.0, -2.0),
+            max: dvec2(2.0, 2.0),
+        };
+        let paths = morph
+            .export_svg_frames(
+                3,
+                &dir,
+                &region,
+                &Builtin::default(),
+                &SvgOptions::default(),
+            )
+            .unwrap();
+        let names: Vec<_> = paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            ["frame_0000.svg", "frame_0001.svg", "frame_0002.svg"]
+        );
+        for path in &paths {
+            let svg = std::fs::read_to_string(path).unwrap();
+            assert!(svg.starts_with("<svg") && svg.contains("<path"));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
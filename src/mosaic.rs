@@ -0,0 +1,203 @@
+//! Mosaics: tilings coloured after a source image.
+//!
+//! See [`Mosaic`].
+use std::collections::HashMap;
+
+use glam::{dvec2, DMat3, DVec2};
+
+use crate::{
+    adjacency::TileCoord,
+    geometry::{self, BoundingBox},
+    image::{Rgb, RgbImage},
+    palette::TileColouring,
+    FillRegionStep, IsohedralTiling,
+};
+
+/// A tiling covering an image, where each tile has the average colour of the pixels it covers.
+///
+/// The actual outlines of the tiles, including their edge curves, are rasterized, and each pixel
+/// is assigned to the tile containing its centre, so every pixel counts towards exactly one tile.
+/// Tiles too small to contain any pixel centre take the colour of the pixel under their centre,
+/// and tiles outside the image aren't part of the mosaic.
+///
+/// A mosaic can be drawn with any renderer, as it implements [`TileColouring`]. Tiles that don't
+/// belong to the mosaic are drawn in black.
+#[derive(Debug, Clone)]
+pub struct Mosaic {
+    region: BoundingBox,
+    /// The colour and number of covered pixels of each tile
+    tiles: HashMap<TileCoord, (Rgb, usize)>,
+}
+
+impl Mosaic {
+    /// Covers `image` with the tiles of `tiling`, drawn with the given edge curves.
+    ///
+    /// `placement` maps pixel coordinates to world coordinates, see [`RgbImage::sample`]. See
+    /// [`IsohedralTiling::outline`] for the format of `edges`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is empty.
+    pub fn new(
+        tiling: &IsohedralTiling,
+        edges: &[Vec<DVec2>],
+        image: &RgbImage,
+        placement: &DMat3,
+    ) -> Self {
+        let (w, h) = (image.width() as f64, image.height() as f64);
+        let corners = [dvec2(0.0, 0.0), dvec2(w, 0.0), dvec2(w, h), dvec2(0.0, h)];
+        let region = BoundingBox::from_points(&corners.map(|c| placement.transform_point2(c)));
+
+        let outline = tiling.outline(edges);
+        let centre = geometry::centroid(tiling.vertices());
+        let to_pixels = placement.inverse();
+        // `fill_region` can miss tiles near the corners of the region, so cover a slightly larger
+        // area and drop the tiles outside the image afterwards
+        let size = BoundingBox::from_points(&outline);
+        let padded = region.expand(size.width().max(size.height()));
+        let mut tiles = HashMap::new();
+        for tile in &tiling.fill_region(padded.min.x, padded.min.y, padded.max.x, padded.max.y) {
+            let m = to_pixels * tile.transform;
+            let poly: Vec<_> = outline.iter().map(|p| m.transform_point2(*p)).collect();
+            let mut sum = [0u64; 3];
+            let mut count = 0;
+            rasterize(&poly, image.width(), image.height(), |x, y| {
+                let p = image.pixel(x, y);
+                sum[0] += p.r as u64;
+                sum[1] += p.g as u64;
+                sum[2] += p.b as u64;
+                count += 1;
+            });
+
+            let colour = if count > 0 {
+                let avg = |s: u64| ((s + count as u64 / 2) / count as u64) as u8;
+                Rgb::new(avg(sum[0]), avg(sum[1]), avg(sum[2]))
+            } else {
+                let p = tile.transform.transform_point2(centre);
+                if !region.contains(p) {
+                    continue;
+                }
+                image.sample(placement, p)
+            };
+            tiles.insert(
+                TileCoord::new(tile.t1, tile.t2, tile.aspect),
+                (colour, count),
+            );
+        }
+
+        Self { region, tiles }
+    }
+
+    /// The bounds of the area covered by the image, in world coordinates.
+    pub fn region(&self) -> BoundingBox {
+        self.region
+    }
+
+    /// The number of tiles in the mosaic.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Whether the mosaic contains no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns the colour of a tile, or `None` if it isn't part of the mosaic.
+    pub fn tile_colour(&self, tile: TileCoord) -> Option<Rgb> {
+        self.tiles.get(&tile).map(|&(colour, _)| colour)
+    }
+
+    /// Returns the number of pixels covered by a tile.
+    pub fn coverage(&self, tile: TileCoord) -> usize {
+        self.tiles.get(&tile).map_or(0, |&(_, count)| count)
+    }
+}
+
+impl TileColouring for Mosaic {
+    fn colour(&self, _tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        self.tile_colour(TileCoord::new(tile.t1, tile.t2, tile.aspect))
+            .unwrap_or_default()
+    }
+}
+
+/// Calls `f` with the coordinates of each pixel of a `width * height` image whose centre is
+/// inside `poly`, given in pixel coordinates.
+///
+/// Pixel centres exactly on an edge are assigned consistently, so that polygons sharing edges
+/// never both get the same pixel.
+fn rasterize<F: FnMut(usize, usize)>(poly: &[DVec2], width: usize, height: usize, mut f: F) {
+    let bounds = BoundingBox::from_points(poly);
+    let y0 = (bounds.min.y - 0.5).ceil().max(0.0) as usize;
+    let y1 = ((bounds.max.y - 0.5).ceil().max(0.0) as usize).min(height);
+
+    let mut xs = vec![];
+    for y in y0..y1 {
+        let yc = y as f64 + 0.5;
+        xs.clear();
+        for (idx, a) in poly.iter().enumerate() {
+            let b = poly[(idx + 1) % poly.len()];
+            // Half-open rule, so vertices on the scanline are only counted once
+            if (a.y <= yc) != (b.y <= yc) {
+                xs.push(a.x + (yc - a.y) / (b.y - a.y) * (b.x - a.x));
+            }
+        }
+        xs.sort_by(|a, b| a.total_cmp(b));
+
+        for span in xs.chunks_exact(2) {
+            let x0 = (span[0] - 0.5).ceil().max(0.0) as usize;
+            let x1 = ((span[1] - 0.5).ceil().max(0.0) as usize).min(width);
+            for x in x0..x1 {
+                f(x, y);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec2, DMat3};
+
+    use crate::{
+        adjacency::TileCoord,
+        get_tiling_type,
+        image::{Rgb, RgbImage},
+        test_utils, IsohedralTiling,
+    };
+
+    use super::Mosaic;
+
+    #[test]
+    fn pixels_are_covered_exactly_once() {
+        let tiling = IsohedralTiling::new(get_tiling_type(6));
+        let edges = test_utils::bumpy_edges(&tiling, dvec2(0.5, 0.2));
+        let red = Rgb::new(255, 0, 0);
+        let blue = Rgb::new(0, 0, 255);
+        let image = RgbImage::from_fn(120, 80, |x, _| if x < 60 { red } else { blue });
+        // 20 pixels per unit, with the y axis pointing up and the origin in the middle
+        let placement =
+            DMat3::from_translation(dvec2(-3.0, 2.0)) * DMat3::from_scale(dvec2(0.05, -0.05));
+
+        let mosaic = Mosaic::new(&tiling, &edges, &image, &placement);
+        let region = mosaic.region();
+        assert!((region.width() - 6.0).abs() < 1e-9);
+
+        let mut total = 0;
+        let mut colours = vec![];
+        for t1 in -20..=20 {
+            for t2 in -20..=20 {
+                for aspect in 0..tiling.num_aspects() {
+                    let coord = TileCoord::new(t1, t2, aspect);
+                    total += mosaic.coverage(coord);
+                    colours.extend(mosaic.tile_colour(coord));
+                }
+            }
+        }
+        assert_eq!(colours.len(), mosaic.len());
+        assert_eq!(total, 120 * 80);
+        assert!(colours.contains(&red));
+        assert!(colours.contains(&blue));
+        // Tiles across the middle of the image get a mix of both colours
+        assert!(colours.iter().any(|c| c.r > 0 && c.b > 0));
+    }
+}
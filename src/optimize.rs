@@ -0,0 +1,358 @@
+//! Searching the parameter space of a tiling for shapes that minimise an objective.
+//!
+//! See [`IsohedralTiling::optimize`].
+use std::f64::consts::PI;
+
+use glam::DVec2;
+
+use crate::{geometry, EdgeShape, IsohedralTiling};
+
+/// A function of the shape of a tile that the optimizer tries to minimise.
+///
+/// Any closure taking a tiling and its edge curves and returning an `f64` can be used as an
+/// objective.
+pub trait Objective {
+    /// Evaluates the objective for the given tiling drawn with the given edge curves. Lower
+    /// values are better.
+    fn evaluate(&self, tiling: &IsohedralTiling, edges: &[Vec<DVec2>]) -> f64;
+}
+
+impl<F> Objective for F
+where
+    F: Fn(&IsohedralTiling, &[Vec<DVec2>]) -> f64,
+{
+    fn evaluate(&self, tiling: &IsohedralTiling, edges: &[Vec<DVec2>]) -> f64 {
+        self(tiling, edges)
+    }
+}
+
+/// Makes tiles as round as possible.
+///
+/// The value is `1 - q`, where `q = 4πA / P²` is the isoperimetric quotient of the tile, which is
+/// `1` for a circle and smaller for any other shape.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Roundness;
+
+impl Objective for Roundness {
+    fn evaluate(&self, tiling: &IsohedralTiling, edges: &[Vec<DVec2>]) -> f64 {
+        let m = tiling.measures_with_edges(edges);
+        1.0 - 4.0 * PI * m.area / (m.perimeter * m.perimeter)
+    }
+}
+
+/// Makes the ratio between the lengths of the two translation vectors, `|t1| / |t2|`, as close as
+/// possible to a target value.
+#[derive(Debug, Clone, Copy)]
+pub struct UnitCellAspectRatio {
+    /// The desired value of `|t1| / |t2|`.
+    pub target: f64,
+}
+
+impl Objective for UnitCellAspectRatio {
+    fn evaluate(&self, tiling: &IsohedralTiling, _edges: &[Vec<DVec2>]) -> f64 {
+        let ratio = tiling.t1().length() / tiling.t2().length();
+        (ratio / self.target).ln().powi(2)
+    }
+}
+
+/// Makes tiles look as similar as possible to a target polygon, regardless of position, size and
+/// orientation.
+///
+/// See [`geometry::shape_distance`].
+#[derive(Debug, Clone)]
+pub struct DistanceToPolygon {
+    /// The closed target polygon.
+    pub target: Vec<DVec2>,
+}
+
+impl Objective for DistanceToPolygon {
+    fn evaluate(&self, tiling: &IsohedralTiling, edges: &[Vec<DVec2>]) -> f64 {
+        geometry::shape_distance(&tiling.outline(edges), &self.target)
+    }
+}
+
+/// The settings of the optimizer.
+#[derive(Debug, Clone)]
+pub struct OptimizeOptions {
+    /// The maximum number of iterations.
+    pub max_iterations: usize,
+    /// The search stops when the values of the objective over the search simplex are all within
+    /// this distance of each other.
+    pub tolerance: f64,
+    /// The size of the initial steps taken from the starting point along each variable.
+    pub initial_step: f64,
+    /// Whether to also move the control points of the edge curves (except for the end points of
+    /// the curves, and the curves of `I` edges, which must stay symmetric).
+    pub optimize_edges: bool,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            tolerance: 1e-9,
+            initial_step: 0.05,
+            optimize_edges: false,
+        }
+    }
+}
+
+/// The best configuration found by the optimizer.
+#[derive(Debug, Clone)]
+pub struct OptimizeResult {
+    /// The best parameters found.
+    pub parameters: [f64; 6],
+    /// The best edge curves found. These are the original curves unless
+    /// [`OptimizeOptions::optimize_edges`] is set.
+    pub edges: Vec<Vec<DVec2>>,
+    /// The value of the objective for the best configuration.
+    pub value: f64,
+    /// The number of iterations performed.
+    pub iterations: usize,
+}
+
+impl IsohedralTiling {
+    /// Searches for the parameters (and optionally the edge curves) minimising `objective`,
+    /// starting from the current parameters and the given edge curves.
+    ///
+    /// The search uses the Nelder-Mead simplex method, which doesn't need derivatives of the
+    /// objective. Configurations where the prototile isn't a simple polygon are never returned:
+    /// if the starting configuration isn't one, there is nowhere valid to start from and `None`
+    /// is returned. The tiling itself is not modified: use [`set_parameters`] to apply the result.
+    ///
+    /// See [`outline`] for the format of `edges`.
+    ///
+    /// [`set_parameters`]: IsohedralTiling::set_parameters
+    /// [`outline`]: IsohedralTiling::outline
+    pub fn optimize<O: Objective + ?Sized>(
+        &self,
+        objective: &O,
+        edges: &[Vec<DVec2>],
+        options: &OptimizeOptions,
+    ) -> Option<OptimizeResult> {
+        let mut problem = Problem {
+            tiling: self.clone(),
+            edges: edges.to_vec(),
+            free_edges: vec![],
+            objective,
+        };
+        if options.optimize_edges {
+            problem.free_edges = (0..self.num_edge_shapes())
+                .filter(|&idx| self.edge_shape(idx) != EdgeShape::I)
+                .collect();
+        }
+
+        let start = problem.variables();
+        if !problem.evaluate(&start).is_finite() {
+            return None;
+        }
+        let (best, value, iterations) = nelder_mead(
+            |x| problem.evaluate(x),
+            &start,
+            options.initial_step,
+            options.tolerance,
+            options.max_iterations,
+        );
+        problem.apply(&best);
+
+        let mut parameters = [0.0; 6];
+        problem.tiling.parameters(&mut parameters);
+        Some(OptimizeResult {
+            parameters,
+            edges: problem.edges,
+            value,
+            iterations,
+        })
+    }
+}
+
+/// Maps between a flat list of variables and a tiling configuration.
+struct Problem<'a, O: ?Sized> {
+    tiling: IsohedralTiling,
+    edges: Vec<Vec<DVec2>>,
+    /// The ids of the edge shapes whose control points are variables.
+    free_edges: Vec<usize>,
+    objective: &'a O,
+}
+
+impl<'a, O: Objective + ?Sized> Problem<'a, O> {
+    fn variables(&self) -> Vec<f64> {
+        let mut params = [0.0; 6];
+        self.tiling.parameters(&mut params);
+        let mut x = params[..self.tiling.num_params()].to_vec();
+        for &id in &self.free_edges {
+            let curve = &self.edges[id];
+            for p in &curve[1..curve.len() - 1] {
+                x.push(p.x);
+                x.push(p.y);
+            }
+        }
+        x
+    }
+
+    fn apply(&mut self, x: &[f64]) {
+        let np = self.tiling.num_params();
+        let mut params = [0.0; 6];
+        self.tiling.parameters(&mut params);
+        params[..np].copy_from_slice(&x[..np]);
+        self.tiling.set_parameters(&params);
+
+        let mut rest = &x[np..];
+        for &id in &self.free_edges {
+            let curve = &mut self.edges[id];
+            let len = curve.len();
+            for p in &mut curve[1..len - 1] {
+                *p = DVec2::new(rest[0], rest[1]);
+                rest = &rest[2..];
+            }
+        }
+    }
+
+    fn evaluate(&mut self, x: &[f64]) -> f64 {
+        self.apply(x);
+        if !self.tiling.is_simple_with_edges(&self.edges) {
+            return f64::INFINITY;
+        }
+        let value = self.objective.evaluate(&self.tiling, &self.edges);
+        if value.is_nan() {
+            f64::INFINITY
+        } else {
+            value
+        }
+    }
+}
+
+/// Minimises `f` using the Nelder-Mead method, starting from `start`. Returns the best point
+/// found, its value and the number of iterations.
+fn nelder_mead<F: FnMut(&[f64]) -> f64>(
+    mut f: F,
+    start: &[f64],
+    step: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> (Vec<f64>, f64, usize) {
+    let n = start.len();
+    let start_value = f(start);
+    if n == 0 {
+        return (vec![], start_value, 0);
+    }
+
+    let mut simplex = vec![(start.to_vec(), start_value)];
+    for idx in 0..n {
+        let mut x = start.to_vec();
+        x[idx] += step;
+        let value = f(&x);
+        simplex.push((x, value));
+    }
+
+    let mut iterations = 0;
+    while iterations < max_iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let best = simplex[0].1;
+        let worst = simplex[n].1;
+        // There is no direction to move in if no point of the simplex has a finite value
+        if best.is_infinite() || (worst.is_finite() && worst - best <= tolerance) {
+            break;
+        }
+        iterations += 1;
+
+        // Centroid of all the points but the worst
+        let mut centroid = vec![0.0; n];
+        for (x, _) in &simplex[..n] {
+            for (c, xi) in centroid.iter_mut().zip(x) {
+                *c += xi / n as f64;
+            }
+        }
+        let towards = |t: f64, x: &[f64]| -> Vec<f64> {
+            centroid
+                .iter()
+                .zip(x)
+                .map(|(c, xi)| c + t * (xi - c))
+                .collect()
+        };
+
+        let reflected = towards(-1.0, &simplex[n].0);
+        let reflected_value = f(&reflected);
+        if reflected_value < simplex[0].1 {
+            let expanded = towards(-2.0, &simplex[n].0);
+            let expanded_value = f(&expanded);
+            simplex[n] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+            continue;
+        }
+        if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+            continue;
+        }
+
+        let contracted = if reflected_value < simplex[n].1 {
+            towards(-0.5, &simplex[n].0)
+        } else {
+            towards(0.5, &simplex[n].0)
+        };
+        let contracted_value = f(&contracted);
+        if contracted_value < simplex[n].1.min(reflected_value) {
+            simplex[n] = (contracted, contracted_value);
+            continue;
+        }
+
+        // Shrink everything towards the best point
+        let best_point = simplex[0].0.clone();
+        for (x, value) in &mut simplex[1..] {
+            for (xi, bi) in x.iter_mut().zip(&best_point) {
+                *xi = bi + 0.5 * (*xi - bi);
+            }
+            *value = f(x);
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let (x, value) = simplex.swap_remove(0);
+    (x, value, iterations)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{get_tiling_type, IsohedralTiling};
+
+    use super::{OptimizeOptions, Roundness, UnitCellAspectRatio};
+
+    #[test]
+    fn optimizing_improves_objective() {
+        let tiling = IsohedralTiling::new(get_tiling_type(3));
+        let edges = vec![vec![dvec2(0.0, 0.0), dvec2(0.5, 0.1), dvec2(1.0, 0.0)]; 5];
+        let options = OptimizeOptions {
+            optimize_edges: true,
+            ..Default::default()
+        };
+
+        let before = tiling.measures_with_edges(&edges);
+        let result = tiling.optimize(&Roundness, &edges, &options).unwrap();
+        let mut optimized = tiling.clone();
+        optimized.set_parameters(&result.parameters);
+        let after = optimized.measures_with_edges(&result.edges);
+        let roundness = |m: crate::geometry::Measures| m.area / (m.perimeter * m.perimeter);
+        assert!(roundness(after) > roundness(before));
+        assert!(optimized.is_simple_with_edges(&result.edges));
+
+        let result = tiling
+            .optimize(&UnitCellAspectRatio { target: 2.0 }, &edges, &options)
+            .unwrap();
+        optimized.set_parameters(&result.parameters);
+        let ratio = optimized.t1().length() / optimized.t2().length();
+        assert!((ratio - 2.0).abs() < 1e-3, "{}", ratio);
+
+        // There is nothing to optimise from a degenerate tile
+        let mut degenerate = IsohedralTiling::new(get_tiling_type(0));
+        degenerate.set_parameters(&[0.0; 6]);
+        let straight = vec![vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)]; degenerate.num_edge_shapes()];
+        assert!(degenerate
+            .optimize(&Roundness, &straight, &options)
+            .is_none());
+    }
+}
@@ -0,0 +1,233 @@
+//! Colouring strategies deciding the fill colour of each tile when drawing a tiling.
+//!
+//! All the renderers of the library take a [`TileColouring`], so that the same strategy can be
+//! used whatever the output format.
+use glam::{DMat3, DVec2};
+
+use crate::{
+    colouring::PeriodicColouring,
+    geometry,
+    image::{Rgb, RgbImage},
+    utils::splitmix64,
+    FillRegionStep, IsohedralTiling,
+};
+
+/// The palette used by default, with as many colours as [`IsohedralTiling::colour`] needs.
+pub const DEFAULT_PALETTE: [Rgb; 3] = [
+    Rgb::new(0x4e, 0x79, 0xa7),
+    Rgb::new(0xf2, 0x8e, 0x2b),
+    Rgb::new(0x59, 0xa1, 0x4f),
+];
+
+/// A strategy deciding the fill colour of each tile.
+///
+/// Any closure taking a tiling and a tile from [`fill_region`](IsohedralTiling::fill_region) and
+/// returning an [`Rgb`] colour can be used as a colouring.
+pub trait TileColouring {
+    /// Returns the colour of `tile`, a tile of `tiling`.
+    fn colour(&self, tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb;
+}
+
+impl<F> TileColouring for F
+where
+    F: Fn(&IsohedralTiling, &FillRegionStep) -> Rgb,
+{
+    fn colour(&self, tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        self(tiling, tile)
+    }
+}
+
+/// Picks the colour with the given index, wrapping around the end of the palette.
+fn pick(palette: &[Rgb], idx: usize) -> Rgb {
+    assert!(!palette.is_empty(), "Palettes can't be empty");
+    palette[idx % palette.len()]
+}
+
+/// The centre of the prototile, once mapped onto `tile`.
+fn centre(tiling: &IsohedralTiling, tile: &FillRegionStep) -> DVec2 {
+    tile.transform
+        .transform_point2(geometry::centroid(tiling.vertices()))
+}
+
+/// Colours tiles using the colouring built into the tiling type, see
+/// [`IsohedralTiling::colour`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Builtin {
+    /// The colours, indexed by the value returned by [`IsohedralTiling::colour`].
+    pub palette: Vec<Rgb>,
+}
+
+impl Default for Builtin {
+    fn default() -> Self {
+        Self {
+            palette: DEFAULT_PALETTE.to_vec(),
+        }
+    }
+}
+
+impl TileColouring for Builtin {
+    fn colour(&self, tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        pick(
+            &self.palette,
+            tiling.colour(tile.t1, tile.t2, tile.aspect) as usize,
+        )
+    }
+}
+
+/// Gives the same colour to all the tiles with the same aspect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerAspect {
+    /// The colours, indexed by aspect.
+    pub palette: Vec<Rgb>,
+}
+
+impl TileColouring for PerAspect {
+    fn colour(&self, _tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        pick(&self.palette, tile.aspect)
+    }
+}
+
+/// Picks a pseudo-random colour from the palette for each tile, based on its lattice coordinates
+/// and aspect. The colour of a tile only depends on its position and on the seed.
+///
+/// Unlike [`TileId`](crate::tile_id::TileId), this works for any lattice coordinates, however
+/// far the tiles are from the origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatticeHash {
+    /// The colours to pick from.
+    pub palette: Vec<Rgb>,
+    /// Changing the seed gives a different colouring.
+    pub seed: u64,
+}
+
+impl TileColouring for LatticeHash {
+    fn colour(&self, _tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        // Mix the coordinates in one at a time, so that they can take any value
+        let h = [tile.t1 as u64, tile.t2 as u64, tile.aspect as u64]
+            .iter()
+            .fold(splitmix64(self.seed), |h, &x| splitmix64(h ^ x));
+        pick(
+            &self.palette,
+            (h % self.palette.len().max(1) as u64) as usize,
+        )
+    }
+}
+
+/// Colours tiles with a linear gradient, according to the position of their centre.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gradient {
+    /// The point where the gradient has the colour `from`.
+    pub start: DVec2,
+    /// The point where the gradient has the colour `to`.
+    pub end: DVec2,
+    /// The colour at `start` and before.
+    pub from: Rgb,
+    /// The colour at `end` and after.
+    pub to: Rgb,
+}
+
+impl TileColouring for Gradient {
+    fn colour(&self, tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        let dir = self.end - self.start;
+        let len2 = dir.length_squared();
+        let t = if len2 > 0.0 {
+            (centre(tiling, tile) - self.start).dot(dir) / len2
+        } else {
+            0.0
+        };
+        self.from.lerp(self.to, t)
+    }
+}
+
+/// Colours tiles using a [`PeriodicColouring`], such as the ones returned by
+/// [`IsohedralTiling::perfect_colourings`] or [`IsohedralTiling::proper_colouring`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Periodic {
+    /// The colouring giving the colour index of each tile.
+    pub colouring: PeriodicColouring,
+    /// The colours, indexed by colour index.
+    pub palette: Vec<Rgb>,
+}
+
+impl TileColouring for Periodic {
+    fn colour(&self, _tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        pick(
+            &self.palette,
+            self.colouring.colour(tile.t1, tile.t2, tile.aspect),
+        )
+    }
+}
+
+/// Gives each tile the colour of the pixel of an image under its centre.
+///
+/// See [`RgbImage::sample`] for the meaning of `placement`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageSampled {
+    /// The image to sample.
+    pub image: RgbImage,
+    /// The transform mapping pixel coordinates to world coordinates.
+    pub placement: DMat3,
+}
+
+impl TileColouring for ImageSampled {
+    fn colour(&self, tiling: &IsohedralTiling, tile: &FillRegionStep) -> Rgb {
+        self.image.sample(&self.placement, centre(tiling, tile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{get_tiling_type, image::Rgb, FillRegionStep, IsohedralTiling};
+
+    use super::{Builtin, Gradient, LatticeHash, PerAspect, TileColouring, DEFAULT_PALETTE};
+
+    #[test]
+    fn colourings_follow_tiles() {
+        let tiling = IsohedralTiling::new(get_tiling_type(4));
+        let hash = LatticeHash {
+            palette: DEFAULT_PALETTE.to_vec(),
+            seed: 42,
+        };
+        let gradient = Gradient {
+            start: dvec2(-5.0, 0.0),
+            end: dvec2(5.0, 0.0),
+            from: Rgb::new(0, 0, 0),
+            to: Rgb::new(255, 255, 255),
+        };
+        let per_aspect = PerAspect {
+            palette: vec![Rgb::new(1, 2, 3), Rgb::new(4, 5, 6)],
+        };
+
+        let tiles: Vec<_> = tiling.fill_region(-5.0, -5.0, 5.0, 5.0).iter().collect();
+        for tile in &tiles {
+            assert_eq!(
+                Builtin::default().colour(&tiling, tile),
+                DEFAULT_PALETTE[tiling.colour(tile.t1, tile.t2, tile.aspect) as usize]
+            );
+            assert_eq!(
+                per_aspect.colour(&tiling, tile),
+                per_aspect.palette[tile.aspect % 2]
+            );
+            assert!(DEFAULT_PALETTE.contains(&hash.colour(&tiling, tile)));
+        }
+        // Tiles far away from the origin get a colour too
+        let far = FillRegionStep {
+            t1: isize::MAX,
+            t2: isize::MIN,
+            ..tiles[0]
+        };
+        assert!(DEFAULT_PALETTE.contains(&hash.colour(&tiling, &far)));
+        let left = tiles
+            .iter()
+            .find(|t| t.transform.transform_point2(dvec2(0.0, 0.0)).x < -3.0)
+            .unwrap();
+        assert!(gradient.colour(&tiling, left).r < 128);
+
+        let red = Rgb::from_hex("#ff0000").unwrap();
+        let custom = |_: &IsohedralTiling, _: &FillRegionStep| red;
+        assert_eq!(custom.colour(&tiling, &tiles[0]), red);
+        assert_eq!(red.to_string(), "#ff0000");
+    }
+}
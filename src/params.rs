@@ -0,0 +1,564 @@
+//! Tools for exploring the parameter space of a tiling type.
+//!
+//! Most parameter values don't describe a valid prototile: the vertices can collapse onto each
+//! other, or the edges can cross. The functions in this module help finding out which parameter
+//! values are valid.
+//!
+//! The vertices, translation vectors and aspect transforms of a tiling are all affine functions
+//! of the parameters. This module also exposes the coefficients of these functions, and their
+//! (constant) Jacobians, which can be used for gradient-based optimisation of tile shapes.
+use std::{fmt, ops::RangeInclusive};
+
+use glam::{DMat3, DVec2};
+
+use crate::{
+    geometry,
+    utils::{fill_vector, solve_linear},
+    IsohedralTiling,
+};
+
+/// How far away from its current value a parameter is searched when computing its bounds.
+const MAX_EXTENT: f64 = 100.0;
+
+/// How far away from its default value a parameter can go in the range suggested by
+/// [`ParameterInfo::range`].
+const UI_EXTENT: f64 = 1.0;
+
+/// The damping used when solving for parameters, which makes the solution unique by preferring
+/// the smallest change to the current parameters.
+const DAMPING: f64 = 1e-10;
+
+/// Describes what a parameter of a tiling type controls.
+///
+/// See [`IsohedralTiling::parameter_info`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterInfo {
+    /// The index of the parameter.
+    pub index: usize,
+    /// A short label for the parameter, e.g. `v2.x` for a parameter that moves vertex 2
+    /// horizontally, or `v1+v4.y` for one that moves vertices 1 and 4 vertically.
+    pub name: String,
+    /// A sentence describing which vertices the parameter moves, and in which directions.
+    pub description: String,
+    /// The default value of the parameter for this tiling type.
+    pub default: f64,
+    /// A sensible range of values for a user interface, around the default value. All the
+    /// values in this range give a simple prototile when the other parameters have their default
+    /// value.
+    pub range: RangeInclusive<f64>,
+    /// The vertices moved by the parameter, with the displacement of each vertex when the
+    /// parameter increases by `1`.
+    pub vertex_motions: Vec<(usize, DVec2)>,
+}
+
+/// The error returned when trying to set parameters that would make the prototile invalid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidParameters {
+    /// The rejected parameters.
+    pub parameters: [f64; 6],
+}
+
+impl fmt::Display for InvalidParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the parameters {:?} don't describe a simple prototile",
+            self.parameters
+        )
+    }
+}
+
+impl std::error::Error for InvalidParameters {}
+
+impl IsohedralTiling {
+    /// Whether the straight-edged prototile returned by [`vertices`] is a simple polygon, i.e.
+    /// whether its edges don't cross or touch each other.
+    ///
+    /// [`vertices`]: IsohedralTiling::vertices
+    pub fn is_simple(&self) -> bool {
+        geometry::is_simple(self.vertices())
+    }
+
+    /// Whether the prototile drawn with the given edge curves is a simple polygon.
+    ///
+    /// See [`outline`] for the format of `edges`.
+    ///
+    /// [`outline`]: IsohedralTiling::outline
+    pub fn is_simple_with_edges(&self, edges: &[Vec<DVec2>]) -> bool {
+        geometry::is_simple(&self.outline(edges))
+    }
+
+    /// Sets the vertex parameters, but only if the resulting straight-edged prototile is a simple
+    /// polygon. Otherwise the tiling is left untouched and an error is returned.
+    ///
+    /// See also: [`set_parameters`], [`is_simple`]
+    ///
+    /// [`set_parameters`]: IsohedralTiling::set_parameters
+    /// [`is_simple`]: IsohedralTiling::is_simple
+    pub fn try_set_parameters(&mut self, params: &[f64; 6]) -> Result<(), InvalidParameters> {
+        if self.is_valid_parameters(params) {
+            self.set_parameters(params);
+            Ok(())
+        } else {
+            Err(InvalidParameters {
+                parameters: *params,
+            })
+        }
+    }
+
+    /// Whether the given parameters would give a simple straight-edged prototile.
+    pub fn is_valid_parameters(&self, params: &[f64; 6]) -> bool {
+        geometry::is_simple(&self.vertices_for(params))
+    }
+
+    /// Computes the range of values that the parameter `idx` can take while the straight-edged
+    /// prototile stays a simple polygon, all the other parameters keeping their current value.
+    ///
+    /// The bounds are found numerically, by searching away from the current value. A bound is
+    /// infinite if the prototile stays valid at least up to a distance of 100 from the current
+    /// value. Returns `None` if the current parameters are already invalid.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not smaller than [`num_params`].
+    ///
+    /// [`num_params`]: IsohedralTiling::num_params
+    pub fn parameter_bounds(&self, idx: usize) -> Option<RangeInclusive<f64>> {
+        assert!(
+            idx < self.num_params(),
+            "Tiling type {} only has {} parameters",
+            self.tiling_type(),
+            self.num_params()
+        );
+        if !self.is_valid_parameters(&self.parameters) {
+            return None;
+        }
+
+        Some(self.bounds_around(&self.parameters, idx))
+    }
+
+    /// Describes what the parameter `idx` of the current tiling type controls.
+    ///
+    /// The description only depends on the tiling type, not on the current parameters.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not smaller than [`num_params`].
+    ///
+    /// [`num_params`]: IsohedralTiling::num_params
+    pub fn parameter_info(&self, idx: usize) -> ParameterInfo {
+        assert!(
+            idx < self.num_params(),
+            "Tiling type {} only has {} parameters",
+            self.tiling_type(),
+            self.num_params()
+        );
+        let np = self.num_params();
+        let vertex_motions: Vec<_> = (0..self.num_vertices())
+            .map(|v| (v, self.vertex_jacobian(v)[idx]))
+            .filter(|(_, d)| d.length_squared() > 1e-12)
+            .collect();
+
+        // Name the parameter after the vertices it moves, and the axis they move along if
+        // they all move along the same one
+        let vertices = vertex_motions
+            .iter()
+            .map(|(v, _)| format!("v{}", v))
+            .collect::<Vec<_>>()
+            .join("+");
+        let name = if vertex_motions.is_empty() {
+            format!("p{}", idx)
+        } else if vertex_motions.iter().all(|(_, d)| d.y.abs() < 1e-9) {
+            format!("{}.x", vertices)
+        } else if vertex_motions.iter().all(|(_, d)| d.x.abs() < 1e-9) {
+            format!("{}.y", vertices)
+        } else {
+            vertices
+        };
+        let description = if vertex_motions.is_empty() {
+            "Doesn't move any vertex".to_string()
+        } else {
+            let motions = vertex_motions
+                .iter()
+                .map(|(v, d)| format!("vertex {} along ({:.3}, {:.3})", v, d.x, d.y))
+                .collect::<Vec<_>>();
+            format!("Moves {}", motions.join(", "))
+        };
+
+        let mut defaults = [0.0; 6];
+        defaults[..np].copy_from_slice(self.ttd.default_params);
+        let default = defaults[idx];
+        let bounds = self.bounds_around(&defaults, idx);
+        let range = bounds.start().max(default - UI_EXTENT)..=bounds.end().min(default + UI_EXTENT);
+
+        ParameterInfo {
+            index: idx,
+            name,
+            description,
+            default,
+            range,
+            vertex_motions,
+        }
+    }
+
+    /// Describes all the parameters of the current tiling type.
+    ///
+    /// See [`parameter_info`].
+    ///
+    /// [`parameter_info`]: IsohedralTiling::parameter_info
+    pub fn parameters_info(&self) -> Vec<ParameterInfo> {
+        (0..self.num_params())
+            .map(|idx| self.parameter_info(idx))
+            .collect()
+    }
+
+    /// Computes the parameters that best move vertex `idx` of the prototile to `target`.
+    ///
+    /// Since the vertices are affine functions of the parameters, this is a linear least-squares
+    /// problem: if `target` can't be reached exactly, the closest reachable position is used. When
+    /// several parameter vectors reach the target, the one closest to the current parameters is
+    /// returned. The tiling itself is not modified: pass the result to [`set_parameters`] or
+    /// [`try_set_parameters`] to apply it.
+    ///
+    /// `target` is expressed in the coordinate system of the prototile, like [`vertices`].
+    ///
+    /// [`set_parameters`]: IsohedralTiling::set_parameters
+    /// [`try_set_parameters`]: IsohedralTiling::try_set_parameters
+    /// [`vertices`]: IsohedralTiling::vertices
+    pub fn solve_vertex_drag(&self, idx: usize, target: DVec2) -> [f64; 6] {
+        self.solve_vertex_positions(&[(idx, target)])
+    }
+
+    /// Computes the parameters that best move several vertices to their target positions at the
+    /// same time, e.g. to drag one vertex while pinning another one in place.
+    ///
+    /// `targets` is a list of `(vertex index, target position)` pairs. See
+    /// [`solve_vertex_drag`] for more details.
+    ///
+    /// [`solve_vertex_drag`]: IsohedralTiling::solve_vertex_drag
+    pub fn solve_vertex_positions(&self, targets: &[(usize, DVec2)]) -> [f64; 6] {
+        let np = self.num_params();
+        let mut params = self.parameters;
+        if np == 0 {
+            return params;
+        }
+
+        // Solve the damped normal equations (JᵀJ + λI) Δ = Jᵀr for the parameter change Δ
+        let mut jtj = vec![0.0; np * np];
+        let mut jtr = vec![0.0; np];
+        for &(idx, target) in targets {
+            let jacobian = self.vertex_jacobian(idx);
+            let residual = target - self.verts[idx];
+            for i in 0..np {
+                for j in 0..np {
+                    jtj[i * np + j] += jacobian[i].dot(jacobian[j]);
+                }
+                jtr[i] += jacobian[i].dot(residual);
+            }
+        }
+        for i in 0..np {
+            jtj[i * np + i] += DAMPING;
+        }
+
+        if let Some(delta) = solve_linear(jtj, jtr) {
+            for (p, d) in params.iter_mut().zip(delta) {
+                *p += d;
+            }
+        }
+        params
+    }
+
+    /// The coefficients of the affine function giving the position of vertex `idx` from the
+    /// parameters.
+    ///
+    /// The returned slice contains two rows of [`num_params`]` + 1` values, for the `x` and `y`
+    /// coordinates of the vertex respectively. Each row contains the coefficient of each
+    /// parameter, followed by a constant term, so that e.g.
+    /// `x = row[0] * p[0] + ... + row[n - 1] * p[n - 1] + row[n]`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not smaller than [`num_vertices`].
+    ///
+    /// [`num_params`]: IsohedralTiling::num_params
+    /// [`num_vertices`]: IsohedralTiling::num_vertices
+    pub fn vertex_coefficients(&self, idx: usize) -> &'static [f64] {
+        assert!(idx < self.num_vertices(), "Invalid vertex index {}", idx);
+        let stride = 2 * (self.num_params() + 1);
+        &self.ttd.tiling_vertex_coeffs[idx * stride..(idx + 1) * stride]
+    }
+
+    /// The coefficients of the affine function giving the first translation vector from the
+    /// parameters.
+    ///
+    /// See [`vertex_coefficients`] for the layout of the returned values.
+    ///
+    /// [`vertex_coefficients`]: IsohedralTiling::vertex_coefficients
+    pub fn t1_coefficients(&self) -> &'static [f64] {
+        let stride = 2 * (self.num_params() + 1);
+        &self.ttd.translation_vertex_coeffs[..stride]
+    }
+
+    /// The coefficients of the affine function giving the second translation vector from the
+    /// parameters.
+    ///
+    /// See [`vertex_coefficients`] for the layout of the returned values.
+    ///
+    /// [`vertex_coefficients`]: IsohedralTiling::vertex_coefficients
+    pub fn t2_coefficients(&self) -> &'static [f64] {
+        let stride = 2 * (self.num_params() + 1);
+        &self.ttd.translation_vertex_coeffs[stride..2 * stride]
+    }
+
+    /// The coefficients of the affine function giving the transform of aspect `idx` from the
+    /// parameters.
+    ///
+    /// The returned slice contains six rows of [`num_params`]` + 1` values, one for each of the
+    /// entries of the top two rows of the 3x3 matrix, in row-major order (the bottom row of an
+    /// aspect transform is always `[0, 0, 1]`). Each row has the same layout as in
+    /// [`vertex_coefficients`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not smaller than [`num_aspects`].
+    ///
+    /// [`num_params`]: IsohedralTiling::num_params
+    /// [`vertex_coefficients`]: IsohedralTiling::vertex_coefficients
+    /// [`num_aspects`]: IsohedralTiling::num_aspects
+    pub fn aspect_coefficients(&self, idx: usize) -> &'static [f64] {
+        assert!(idx < self.num_aspects(), "Invalid aspect index {}", idx);
+        let stride = 6 * (self.num_params() + 1);
+        &self.ttd.aspect_xform_coeffs[idx * stride..(idx + 1) * stride]
+    }
+
+    /// The derivatives of vertex `idx` with respect to each parameter.
+    ///
+    /// The `k`-th element of the result is the displacement of the vertex when parameter `k`
+    /// increases by `1`. Since vertices are affine functions of the parameters, the Jacobian
+    /// doesn't depend on the current parameters.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not smaller than [`num_vertices`].
+    ///
+    /// [`num_vertices`]: IsohedralTiling::num_vertices
+    pub fn vertex_jacobian(&self, idx: usize) -> Vec<DVec2> {
+        vector_jacobian(self.vertex_coefficients(idx), self.num_params())
+    }
+
+    /// The derivatives of the first translation vector with respect to each parameter.
+    ///
+    /// See [`vertex_jacobian`].
+    ///
+    /// [`vertex_jacobian`]: IsohedralTiling::vertex_jacobian
+    pub fn t1_jacobian(&self) -> Vec<DVec2> {
+        vector_jacobian(self.t1_coefficients(), self.num_params())
+    }
+
+    /// The derivatives of the second translation vector with respect to each parameter.
+    ///
+    /// See [`vertex_jacobian`].
+    ///
+    /// [`vertex_jacobian`]: IsohedralTiling::vertex_jacobian
+    pub fn t2_jacobian(&self) -> Vec<DVec2> {
+        vector_jacobian(self.t2_coefficients(), self.num_params())
+    }
+
+    /// The derivatives of the transform of aspect `idx` with respect to each parameter.
+    ///
+    /// The `k`-th element of the result is the derivative of the matrix returned by
+    /// [`aspect_transform`] with respect to parameter `k`. Its bottom row is always zero.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not smaller than [`num_aspects`].
+    ///
+    /// [`aspect_transform`]: IsohedralTiling::aspect_transform
+    /// [`num_aspects`]: IsohedralTiling::num_aspects
+    pub fn aspect_jacobian(&self, idx: usize) -> Vec<DMat3> {
+        let np = self.num_params();
+        let coeffs = self.aspect_coefficients(idx);
+        (0..np)
+            .map(|k| {
+                let mut m = DMat3::ZERO;
+                for row in 0..2 {
+                    for col in 0..3 {
+                        m.col_mut(col)[row] = coeffs[(3 * row + col) * (np + 1) + k];
+                    }
+                }
+                m
+            })
+            .collect()
+    }
+
+    /// Returns the vertices of the prototile for the given parameters, without modifying the
+    /// tiling.
+    pub(crate) fn vertices_for(&self, params: &[f64]) -> Vec<DVec2> {
+        let np = self.num_params();
+        let stride = 2 * (np + 1);
+        (0..self.num_vertices())
+            .map(|idx| {
+                let mut v = DVec2::ZERO;
+                fill_vector(
+                    &self.ttd.tiling_vertex_coeffs[idx * stride..],
+                    params,
+                    np,
+                    &mut v,
+                );
+                v
+            })
+            .collect()
+    }
+
+    /// Computes the range of valid values for the parameter `idx`, starting from the (valid)
+    /// parameters `base`.
+    fn bounds_around(&self, base: &[f64; 6], idx: usize) -> RangeInclusive<f64> {
+        let value = base[idx];
+        let lo = self.search_bound(base, idx, -1.0);
+        let hi = self.search_bound(base, idx, 1.0);
+
+        (value - lo)..=(value + hi)
+    }
+
+    /// Finds how far the parameter `idx` can move away from `base` in the direction `dir` before
+    /// the prototile becomes invalid.
+    fn search_bound(&self, base: &[f64; 6], idx: usize, dir: f64) -> f64 {
+        let mut params = *base;
+        let mut valid_at = |dist: f64| {
+            params[idx] = base[idx] + dir * dist;
+            self.is_valid_parameters(&params)
+        };
+
+        // Double the step until the prototile becomes invalid...
+        let mut good = 0.0;
+        let mut bad = 1e-3;
+        while valid_at(bad) {
+            good = bad;
+            bad *= 2.0;
+            if bad > MAX_EXTENT {
+                return f64::INFINITY;
+            }
+        }
+
+        // ...then bisect to find the boundary
+        while bad - good > 1e-9 {
+            let mid = 0.5 * (good + bad);
+            if valid_at(mid) {
+                good = mid;
+            } else {
+                bad = mid;
+            }
+        }
+
+        good
+    }
+}
+
+/// Extracts the linear part of the affine function of a 2D vector.
+fn vector_jacobian(coeffs: &[f64], np: usize) -> Vec<DVec2> {
+    (0..np)
+        .map(|k| DVec2::new(coeffs[k], coeffs[np + 1 + k]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::DVec2;
+
+    use crate::{get_tiling_type, IsohedralTiling};
+
+    #[test]
+    fn default_parameters_are_within_bounds() {
+        for n in 0..81 {
+            let tiling = IsohedralTiling::new(get_tiling_type(n));
+            assert!(tiling.is_simple(), "{}", tiling.tiling_type());
+
+            let mut params = [0.0; 6];
+            tiling.parameters(&mut params);
+            for (idx, value) in params[..tiling.num_params()].iter().enumerate() {
+                let bounds = tiling.parameter_bounds(idx).unwrap();
+                assert!(bounds.start() < value && value < bounds.end());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        let mut tiling = IsohedralTiling::new(get_tiling_type(0));
+        let mut params = [0.0; 6];
+        tiling.parameters(&mut params);
+
+        let bounds = tiling.parameter_bounds(0).unwrap();
+        let mut invalid = params;
+        invalid[0] = bounds.start() - 0.1;
+        assert!(tiling.try_set_parameters(&invalid).is_err());
+        assert!(tiling.is_simple());
+
+        let mut valid = params;
+        valid[0] = bounds.start() + 0.01;
+        assert!(tiling.try_set_parameters(&valid).is_ok());
+    }
+
+    #[test]
+    fn drag_vertex() {
+        let mut tiling = IsohedralTiling::new(get_tiling_type(5));
+        let mut params = [0.0; 6];
+        tiling.parameters(&mut params);
+
+        // Move a vertex to a position we know is reachable
+        let mut moved = params;
+        moved[1] += 0.05;
+        let target = tiling.vertices_for(&moved)[1];
+
+        let solved = tiling.solve_vertex_drag(1, target);
+        tiling.set_parameters(&solved);
+        assert!(tiling.vertex(1).abs_diff_eq(target, 1e-6));
+
+        // Pinning a vertex keeps it in place
+        let pinned = *tiling.vertex(0);
+        let solved =
+            tiling.solve_vertex_positions(&[(1, target + DVec2::new(0.01, 0.0)), (0, pinned)]);
+        tiling.set_parameters(&solved);
+        assert!(tiling.vertex(0).abs_diff_eq(pinned, 1e-6));
+    }
+
+    #[test]
+    fn jacobians_match_finite_differences() {
+        for n in [0, 17, 42, 80] {
+            let mut tiling = IsohedralTiling::new(get_tiling_type(n));
+            let mut params = [0.0; 6];
+            tiling.parameters(&mut params);
+            let vertex = tiling.vertex_jacobian(2);
+            let t1 = tiling.t1_jacobian();
+            let t2 = tiling.t2_jacobian();
+            let aspect = tiling.aspect_jacobian(tiling.num_aspects() - 1);
+
+            let v0 = *tiling.vertex(2);
+            let t10 = *tiling.t1();
+            let t20 = *tiling.t2();
+            let a0 = *tiling.aspect_transform(tiling.num_aspects() - 1);
+            for k in 0..tiling.num_params() {
+                let mut moved = params;
+                moved[k] += 1.0;
+                tiling.set_parameters(&moved);
+                assert!((*tiling.vertex(2) - v0).abs_diff_eq(vertex[k], 1e-9));
+                assert!((*tiling.t1() - t10).abs_diff_eq(t1[k], 1e-9));
+                assert!((*tiling.t2() - t20).abs_diff_eq(t2[k], 1e-9));
+                let da = *tiling.aspect_transform(tiling.num_aspects() - 1) - a0;
+                assert!(da.abs_diff_eq(aspect[k], 1e-9));
+            }
+            tiling.set_parameters(&params);
+        }
+    }
+
+    #[test]
+    fn parameter_info() {
+        let tiling = IsohedralTiling::new(get_tiling_type(0));
+        let infos = tiling.parameters_info();
+        assert_eq!(infos.len(), tiling.num_params());
+        for info in &infos {
+            assert!(!info.vertex_motions.is_empty());
+            assert!(info.range.contains(&info.default));
+        }
+    }
+}
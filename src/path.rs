@@ -0,0 +1,198 @@
+//! Shortest paths and distance fields over the tiles of a tiling.
+//!
+//! See [`Pathfinder`].
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use glam::DVec2;
+
+use crate::{
+    adjacency::{Adjacency, Neighbourhood, TileCoord},
+    geometry, IsohedralTiling,
+};
+
+/// Finds shortest paths between tiles, moving from neighbour to neighbour.
+///
+/// Distances are counted in steps between neighbouring tiles (hops). The plane being infinite,
+/// all the searches are limited to a maximum distance. The tiles that can be crossed are given by
+/// a `passable` function, which is useful to model obstacles.
+#[derive(Debug, Clone)]
+pub struct Pathfinder {
+    tiling: IsohedralTiling,
+    adjacency: Adjacency,
+    neighbourhood: Neighbourhood,
+    /// The centre of the prototile
+    centre: DVec2,
+    /// The largest distance between the centres of two neighbouring tiles
+    max_step: f64,
+}
+
+impl Pathfinder {
+    /// Computes the distance from the closest source to every passable tile reachable in at most
+    /// `max_distance` steps. Sources are at distance `0`, and are included even if they aren't
+    /// passable.
+    pub fn distances<F: Fn(TileCoord) -> bool>(
+        &self,
+        sources: &[TileCoord],
+        max_distance: usize,
+        passable: F,
+    ) -> HashMap<TileCoord, usize> {
+        let mut distances: HashMap<_, _> = sources.iter().map(|&s| (s, 0)).collect();
+        let mut queue: VecDeque<_> = sources.iter().copied().collect();
+        while let Some(tile) = queue.pop_front() {
+            let d = distances[&tile];
+            if d == max_distance {
+                continue;
+            }
+            for other in self.adjacency.neighbourhood(tile, self.neighbourhood) {
+                if !distances.contains_key(&other) && passable(other) {
+                    distances.insert(other, d + 1);
+                    queue.push_back(other);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Finds a shortest path from `from` to `to` going only through passable tiles, using the A*
+    /// algorithm. Returns the tiles of the path, including both ends, or `None` if there is no
+    /// path of at most `max_distance` steps.
+    ///
+    /// Both ends must be passable.
+    pub fn shortest_path<F: Fn(TileCoord) -> bool>(
+        &self,
+        from: TileCoord,
+        to: TileCoord,
+        max_distance: usize,
+        passable: F,
+    ) -> Option<Vec<TileCoord>> {
+        if !passable(from) || !passable(to) {
+            return None;
+        }
+
+        // A lower bound on the number of steps left, since each step moves the centre of the
+        // current tile by at most `max_step`
+        let target = self.centre_of(to);
+        let heuristic = |tile: TileCoord| {
+            let d = self.centre_of(tile).distance(target) / self.max_step;
+            (d - 1e-9).ceil().max(0.0) as usize
+        };
+
+        let mut parents = HashMap::new();
+        let mut costs = HashMap::from([(from, 0)]);
+        let mut open = BinaryHeap::from([Reverse((heuristic(from), 0, from))]);
+        while let Some(Reverse((_, cost, tile))) = open.pop() {
+            if tile == to {
+                let mut path = vec![to];
+                while let Some(&parent) = parents.get(path.last()?) {
+                    path.push(parent);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if cost > costs[&tile] {
+                // Already reached with a lower cost
+                continue;
+            }
+            for other in self.adjacency.neighbourhood(tile, self.neighbourhood) {
+                let next = cost + 1;
+                let estimate = next + heuristic(other);
+                if estimate > max_distance
+                    || costs.get(&other).is_some_and(|&c| c <= next)
+                    || !passable(other)
+                {
+                    continue;
+                }
+                costs.insert(other, next);
+                parents.insert(other, tile);
+                open.push(Reverse((estimate, next, other)));
+            }
+        }
+
+        None
+    }
+
+    /// The centre of a tile, in world coordinates.
+    fn centre_of(&self, tile: TileCoord) -> DVec2 {
+        self.tiling
+            .tile_transform(tile.t1, tile.t2, tile.aspect)
+            .transform_point2(self.centre)
+    }
+}
+
+impl IsohedralTiling {
+    /// Creates a [`Pathfinder`] moving between tiles of the given kind of neighbourhood.
+    ///
+    /// The pathfinder uses the current parameters of the tiling to guide its searches, but the
+    /// paths it finds don't depend on them.
+    pub fn pathfinder(&self, neighbourhood: Neighbourhood) -> Pathfinder {
+        let adjacency = self.adjacency();
+        let mut pathfinder = Pathfinder {
+            tiling: self.clone(),
+            adjacency,
+            neighbourhood,
+            centre: geometry::centroid(self.vertices()),
+            max_step: 0.0,
+        };
+        for aspect in 0..self.num_aspects() {
+            let tile = TileCoord::new(0, 0, aspect);
+            let centre = pathfinder.centre_of(tile);
+            for other in pathfinder.adjacency.neighbourhood(tile, neighbourhood) {
+                let step = pathfinder.centre_of(other).distance(centre);
+                pathfinder.max_step = pathfinder.max_step.max(step);
+            }
+        }
+
+        pathfinder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        adjacency::{Neighbourhood, TileCoord},
+        get_tiling_type, IsohedralTiling, TilingType,
+    };
+
+    #[test]
+    fn paths_match_distances() {
+        // Squares, where distances along edges are Manhattan distances
+        let squares = IsohedralTiling::new(TilingType(41));
+        let pathfinder = squares.pathfinder(Neighbourhood::Edge);
+        let origin = TileCoord::new(0, 0, 0);
+        let distances = pathfinder.distances(&[origin], 3, |_| true);
+        for k in 1..=3 {
+            assert_eq!(distances.values().filter(|&&d| d == k).count(), 4 * k);
+        }
+
+        let target = TileCoord::new(3, 2, 0);
+        let path = pathfinder
+            .shortest_path(origin, target, 10, |_| true)
+            .unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!((path[0], path[5]), (origin, target));
+        // A wall with a gap forces a detour
+        let wall = |t: TileCoord| t.t1 != 1 || t.t2 == 4;
+        let path = pathfinder.shortest_path(origin, target, 20, wall).unwrap();
+        assert_eq!(path.len(), 10);
+        assert!(path.iter().all(|&t| wall(t)));
+        assert_eq!(pathfinder.shortest_path(origin, target, 8, wall), None);
+
+        // A* finds paths as short as the distances found by BFS
+        let tiling = IsohedralTiling::new(get_tiling_type(6));
+        let pathfinder = tiling.pathfinder(Neighbourhood::Edge);
+        let passable = |t: TileCoord| (t.t1 + 2 * t.t2) % 5 != 3;
+        let distances = pathfinder.distances(&[origin], 6, passable);
+        for (&tile, &d) in &distances {
+            let path = pathfinder.shortest_path(origin, tile, 6, passable).unwrap();
+            assert_eq!(path.len(), d + 1);
+            let adjacency = tiling.adjacency();
+            assert!(path
+                .windows(2)
+                .all(|w| adjacency.neighbours(w[0]).any(|t| t == w[1])));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Larger periods of a tiling, made of several copies of its unit cell.
+//!
+//! See [`Supercell`].
+use glam::DVec2;
+
+use crate::{adjacency::TileCoord, colouring::PeriodicColouring, IsohedralTiling};
+
+/// A supercell of a tiling: the parallelogram spanned by two translations of the lattice, given
+/// in lattice coordinates.
+///
+/// The rows of the matrix are the new translation vectors: `[a, b]` stands for `a * t1 + b * t2`.
+/// The supercell is made of as many unit cells as the absolute value of the determinant of the
+/// matrix, and its copies by the new translations cover the plane exactly.
+///
+/// Two lattice cells are equivalent if they differ by a combination of the new translations.
+/// Each cell is equivalent to exactly one of the cells returned by [`Supercell::cells`], given by
+/// [`Supercell::wrap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Supercell {
+    matrix: [[isize; 2]; 2],
+}
+
+impl Supercell {
+    /// Creates a supercell from the lattice coordinates of its two translation vectors. Returns
+    /// `None` if they are parallel.
+    pub fn new(matrix: [[isize; 2]; 2]) -> Option<Self> {
+        let supercell = Self { matrix };
+        (supercell.determinant() != 0).then_some(supercell)
+    }
+
+    /// Creates the supercell made of `n1` copies of the unit cell along `t1` and `n2` along
+    /// `t2`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `n1` or `n2` is zero.
+    pub fn diagonal(n1: usize, n2: usize) -> Self {
+        Self::new([[n1 as isize, 0], [0, n2 as isize]]).expect("The supercell can't be empty")
+    }
+
+    /// The lattice coordinates of the translation vectors of the supercell.
+    pub fn matrix(&self) -> [[isize; 2]; 2] {
+        self.matrix
+    }
+
+    /// The number of unit cells in the supercell.
+    pub fn num_cells(&self) -> usize {
+        self.determinant().unsigned_abs()
+    }
+
+    /// The translation vectors of the supercell.
+    pub fn vectors(&self, tiling: &IsohedralTiling) -> [DVec2; 2] {
+        self.matrix
+            .map(|[a, b]| a as f64 * *tiling.t1() + b as f64 * *tiling.t2())
+    }
+
+    /// Whether the translation with lattice coordinates `(t1, t2)` is a combination of the
+    /// translation vectors of the supercell, i.e. whether it maps each cell to an equivalent one.
+    pub fn is_period(&self, t1: isize, t2: isize) -> bool {
+        self.wrap(t1, t2) == (0, 0)
+    }
+
+    /// Returns the cell of the supercell equivalent to the lattice cell `(t1, t2)`.
+    pub fn wrap(&self, t1: isize, t2: isize) -> (isize, isize) {
+        let [[a, b], [c, d]] = self.matrix;
+        // The coordinates of the cell in the basis of the supercell are `(s, r) / det`, rounded
+        // down to get the copy of the supercell the cell is in
+        let (s, r, det) = (t1 * d - t2 * c, t2 * a - t1 * b, self.determinant());
+        let (s, r) = if det > 0 {
+            (s.div_euclid(det), r.div_euclid(det))
+        } else {
+            ((-s).div_euclid(-det), (-r).div_euclid(-det))
+        };
+        (t1 - s * a - r * c, t2 - s * b - r * d)
+    }
+
+    /// Returns the lattice cells inside the supercell: the cells whose lattice coordinates are
+    /// in the half-open parallelogram spanned by the translation vectors from the origin.
+    pub fn cells(&self) -> Vec<(isize, isize)> {
+        let [[a, b], [c, d]] = self.matrix;
+        let xs = [0, a, c, a + c];
+        let ys = [0, b, d, b + d];
+        let (xmin, xmax) = (*xs.iter().min().unwrap(), *xs.iter().max().unwrap());
+        let (ymin, ymax) = (*ys.iter().min().unwrap(), *ys.iter().max().unwrap());
+
+        (xmin..=xmax)
+            .flat_map(|t1| (ymin..=ymax).map(move |t2| (t1, t2)))
+            .filter(|&(t1, t2)| self.wrap(t1, t2) == (t1, t2))
+            .collect()
+    }
+
+    /// Returns the tiles of all the cells of the supercell, with all their aspects.
+    pub fn tiles(&self, tiling: &IsohedralTiling) -> Vec<TileCoord> {
+        self.cells()
+            .into_iter()
+            .flat_map(|(t1, t2)| {
+                (0..tiling.num_aspects()).map(move |aspect| TileCoord::new(t1, t2, aspect))
+            })
+            .collect()
+    }
+
+    /// Returns an equivalent supercell, with the same translations, whose vectors are as short
+    /// as possible (Gauss lattice reduction).
+    ///
+    /// The first vector is a shortest translation of the supercell, the second one is a shortest
+    /// translation independent from the first, and they make a counter-clockwise basis.
+    pub fn reduced(&self, tiling: &IsohedralTiling) -> Self {
+        let vector = |[a, b]: [isize; 2]| a as f64 * *tiling.t1() + b as f64 * *tiling.t2();
+        let [mut u, mut v] = self.matrix;
+        let eps = 1e-9 * (tiling.t1().length_squared() + tiling.t2().length_squared());
+        loop {
+            if vector(u).length_squared() > vector(v).length_squared() {
+                std::mem::swap(&mut u, &mut v);
+            }
+            // Stop once `v` can't get shorter, which includes the tie `u . v = |u|^2 / 2` of
+            // hexagonal lattices where `v - u` and `v + u` are as long as each other
+            let (dot, norm) = (vector(u).dot(vector(v)), vector(u).length_squared());
+            if 2.0 * dot.abs() <= norm + eps {
+                break;
+            }
+            let m = (dot / norm).round() as isize;
+            v = [v[0] - m * u[0], v[1] - m * u[1]];
+        }
+        if vector(u).perp_dot(vector(v)) < 0.0 {
+            v = [-v[0], -v[1]];
+        }
+
+        Self { matrix: [u, v] }
+    }
+
+    fn determinant(&self) -> isize {
+        let [[a, b], [c, d]] = self.matrix;
+        a * d - b * c
+    }
+}
+
+impl PeriodicColouring {
+    /// The supercell of the translations preserving the colour of every tile, see
+    /// [`PeriodicColouring::translations`].
+    pub fn supercell(&self) -> Supercell {
+        Supercell {
+            matrix: self.translations(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{data::TILING_TYPES, get_tiling_type, IsohedralTiling, TilingType};
+
+    use super::Supercell;
+
+    #[test]
+    fn supercells_tile_the_lattice() {
+        assert_eq!(Supercell::new([[2, 4], [1, 2]]), None);
+        let tiling = IsohedralTiling::new(get_tiling_type(6));
+
+        for matrix in [[[3, 0], [0, 2]], [[2, 1], [-1, 3]], [[1, 5], [0, -3]]] {
+            let supercell = Supercell::new(matrix).unwrap();
+            let cells = supercell.cells();
+            assert_eq!(cells.len(), supercell.num_cells());
+            assert_eq!(
+                supercell.tiles(&tiling).len(),
+                cells.len() * tiling.num_aspects()
+            );
+            for t1 in -8..8 {
+                for t2 in -8..8 {
+                    let wrapped = supercell.wrap(t1, t2);
+                    assert!(cells.contains(&wrapped));
+                    assert!(supercell.is_period(t1 - wrapped.0, t2 - wrapped.1));
+                }
+            }
+
+            let reduced = supercell.reduced(&tiling);
+            assert_eq!(reduced.num_cells(), supercell.num_cells());
+            for [a, b] in reduced.matrix() {
+                assert!(supercell.is_period(a, b));
+            }
+            for [a, b] in supercell.matrix() {
+                assert!(reduced.is_period(a, b));
+            }
+            let [u, v] = reduced.vectors(&tiling);
+            assert!(u.length() <= v.length() + 1e-9);
+            assert!(u.dot(v).abs() <= u.length_squared() / 2.0 + 1e-9);
+            assert!(u.perp_dot(v) > 0.0);
+        }
+
+        // Hexagonal lattices have ties between equally short vectors
+        for &t in TILING_TYPES.iter() {
+            let tiling = IsohedralTiling::new(TilingType(t));
+            for n in 1..=3 {
+                let supercell = Supercell::diagonal(n, n);
+                let reduced = supercell.reduced(&tiling);
+                assert_eq!(reduced.num_cells(), supercell.num_cells(), "IH{}", t);
+                let [u, v] = reduced.vectors(&tiling);
+                assert!(u.length() <= v.length() + 1e-9, "IH{}", t);
+                assert!(u.dot(v).abs() <= u.length_squared() / 2.0 + 1e-9, "IH{}", t);
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Exporting tilings as SVG images.
+use std::fmt::Write;
+
+use glam::DVec2;
+
+use crate::{geometry::BoundingBox, palette::TileColouring, IsohedralTiling};
+
+/// The settings used when drawing a tiling as SVG.
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// The width of the image, in pixels. The height is computed from the aspect ratio of the
+    /// region being drawn.
+    pub width: f64,
+    /// The colour of the outlines of the tiles, as a CSS colour.
+    pub stroke: String,
+    /// The width of the outlines of the tiles, in pixels.
+    pub stroke_width: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            stroke: "#000000".to_string(),
+            stroke_width: 1.0,
+        }
+    }
+}
+
+/// Draws the tiles covering `region` as an SVG document, filling each tile with the colour given
+/// by `colouring`.
+///
+/// See [`IsohedralTiling::outline`] for the format of `edges`.
+pub fn render_svg<C: TileColouring + ?Sized>(
+    tiling: &IsohedralTiling,
+    edges: &[Vec<DVec2>],
+    region: &BoundingBox,
+    colouring: &C,
+    options: &SvgOptions,
+) -> String {
+    let mut svg = String::new();
+    write_header(&mut svg, region, options);
+
+    let outline = tiling.outline(edges);
+    for tile in &tiling.fill_region(region.min.x, region.min.y, region.max.x, region.max.y) {
+        let fill = colouring.colour(tiling, &tile).to_string();
+        let points: Vec<_> = outline
+            .iter()
+            .map(|p| tile.transform.transform_point2(*p))
+            .collect();
+        write_polygon(&mut svg, &points, &fill);
+    }
+
+    write_footer(&mut svg);
+    svg
+}
+
+/// Writes the opening tags of the document, setting things up so that the content can be drawn
+/// using world coordinates.
+pub(crate) fn write_header(svg: &mut String, region: &BoundingBox, options: &SvgOptions) {
+    let height = options.width * region.height() / region.width();
+    // Writing to a `String` can't fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        options.width,
+        height,
+        region.min.x,
+        -region.max.y,
+        region.width(),
+        region.height()
+    );
+    // Flip the y axis so that it points up like in the rest of the library
+    let _ = writeln!(
+        svg,
+        r#"<g transform="scale(1,-1)" stroke="{}" stroke-width="{}" stroke-linejoin="round">"#,
+        options.stroke, options.stroke_width
+    );
+}
+
+/// Writes a closed polygon filled with the given CSS colour.
+pub(crate) fn write_polygon(svg: &mut String, points: &[DVec2], fill: &str) {
+    svg.push_str("<path d=\"");
+    for (idx, p) in points.iter().enumerate() {
+        let _ = write!(
+            svg,
+            "{}{:.5},{:.5} ",
+            if idx == 0 { "M" } else { "L" },
+            p.x,
+            p.y
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"Z" fill="{}" vector-effect="non-scaling-stroke"/>"#,
+        fill
+    );
+}
+
+/// Writes the closing tags of the document.
+pub(crate) fn write_footer(svg: &mut String) {
+    svg.push_str("</g>\n</svg>\n");
+}
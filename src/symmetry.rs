@@ -0,0 +1,425 @@
+//! The symmetry elements of a tiling: rotation centres, mirror lines and glide-reflection axes.
+//!
+//! See [`IsohedralTiling::symmetry_elements`].
+use std::{
+    f64::consts::{PI, TAU},
+    fmt::Write,
+};
+
+use glam::{dvec2, DMat2, DMat3, DVec2};
+
+use crate::{
+    geometry::BoundingBox,
+    svg::{self, SvgOptions},
+    IsohedralTiling,
+};
+
+/// A point around which the tiling has a rotational symmetry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotationCentre {
+    /// The position of the centre.
+    pub centre: DVec2,
+    /// The order of the rotation: the tiling is unchanged by a rotation of `1 / order` of a full
+    /// turn around the centre. Always 2, 3, 4 or 6.
+    pub order: usize,
+}
+
+/// A line of the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Axis {
+    /// A point on the line.
+    pub point: DVec2,
+    /// The direction of the line, as a unit vector.
+    pub direction: DVec2,
+}
+
+/// A line along which the tiling has a glide-reflection symmetry, but no reflection symmetry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlideAxis {
+    /// The line of the reflection.
+    pub axis: Axis,
+    /// The shortest distance the tiling can be translated along the axis after being reflected
+    /// across it to be unchanged.
+    pub glide: f64,
+}
+
+/// The symmetry elements of a tiling inside a region, as returned by
+/// [`IsohedralTiling::symmetry_elements`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymmetryElements {
+    /// The rotation centres inside the region.
+    pub rotations: Vec<RotationCentre>,
+    /// The mirror lines crossing the region. The point of each line is the closest one to the
+    /// centre of the region.
+    pub mirrors: Vec<Axis>,
+    /// The glide-reflection axes crossing the region, other than mirror lines. The point of each
+    /// axis is the closest one to the centre of the region.
+    pub glides: Vec<GlideAxis>,
+}
+
+impl SymmetryElements {
+    /// Draws the symmetry elements inside `region` as an SVG document with a transparent
+    /// background, using the usual notation for wallpaper groups: mirror lines are solid lines,
+    /// glide axes are dashed lines, and rotation centres are polygons with as many sides as their
+    /// order (a lens for order 2).
+    ///
+    /// The document uses the same coordinates as [`render_svg`](crate::svg::render_svg), so when
+    /// both are given the same region and options, the overlay lines up with the tiles.
+    pub fn to_svg(&self, region: &BoundingBox, options: &SvgOptions) -> String {
+        let mut doc = String::new();
+        svg::write_header(&mut doc, region, options);
+        // The size of a pixel, in world units
+        let pixel = region.width() / options.width;
+
+        // Writing to a `String` can't fail
+        for (axes, dashes) in [
+            (self.mirrors.clone(), None),
+            (
+                self.glides.iter().map(|g| g.axis).collect(),
+                Some(6.0 * options.stroke_width),
+            ),
+        ] {
+            for axis in axes {
+                if let Some((a, b)) = clip_line(&axis, region) {
+                    let _ = writeln!(
+                        doc,
+                        r#"<line x1="{:.5}" y1="{:.5}" x2="{:.5}" y2="{:.5}" stroke-width="{}"{} vector-effect="non-scaling-stroke"/>"#,
+                        a.x,
+                        a.y,
+                        b.x,
+                        b.y,
+                        2.0 * options.stroke_width,
+                        dashes
+                            .map(|d| format!(r#" stroke-dasharray="{} {}""#, d, d))
+                            .unwrap_or_default()
+                    );
+                }
+            }
+        }
+
+        let radius = 6.0 * pixel;
+        for rotation in &self.rotations {
+            let points: Vec<_> = if rotation.order == 2 {
+                // A lens, approximated by two circular arcs
+                let arc = |from: f64| {
+                    (0..=8).map(move |i| {
+                        let angle = from + PI / 3.0 * (i as f64 / 8.0 - 0.5);
+                        let c = dvec2(from.cos(), from.sin()) * -radius * 0.6;
+                        c + dvec2(angle.cos(), angle.sin()) * radius * 1.2
+                    })
+                };
+                arc(0.0).chain(arc(PI)).collect()
+            } else {
+                (0..rotation.order)
+                    .map(|i| {
+                        let angle = PI / 2.0 + TAU * i as f64 / rotation.order as f64;
+                        dvec2(angle.cos(), angle.sin()) * radius
+                    })
+                    .collect()
+            };
+            let points: Vec<_> = points.iter().map(|p| rotation.centre + *p).collect();
+            svg::write_polygon(&mut doc, &points, "#000000");
+        }
+
+        svg::write_footer(&mut doc);
+        doc
+    }
+}
+
+impl IsohedralTiling {
+    /// Computes the symmetry elements of the tiling inside `region`, using the current
+    /// parameters.
+    ///
+    /// The symmetries come from the aspect transforms and the translation vectors, along with the
+    /// symmetries of the tile itself that the tiling type requires. They are the symmetries of
+    /// the tiling when the edges have generic shapes: a tile whose edges happen to give it more
+    /// symmetries (like the straight-edged squares of a grid) makes the tiling more symmetric than
+    /// reported.
+    pub fn symmetry_elements(&self, region: &BoundingBox) -> SymmetryElements {
+        let scale = self.t1.length() + self.t2.length();
+        let eps = 1e-6 * scale;
+        let lattice = DMat2::from_cols(self.t1, self.t2);
+        let inverse = lattice.inverse();
+        let centre = region.center();
+        let radius = region.min.distance(region.max) / 2.0;
+
+        let mut elements = SymmetryElements::default();
+        // The glide distances found for each reflection axis, as (normal, offset, glide)
+        let mut axes: Vec<(DVec2, f64, f64)> = vec![];
+        for m in self.point_symmetries() {
+            // The symmetry as x -> linear * x + offset
+            let linear = DMat2::from_cols(m.x_axis.truncate(), m.y_axis.truncate());
+            let offset = m.z_axis.truncate();
+            let reflected = linear.determinant() < 0.0;
+            if !reflected && linear.abs_diff_eq(&DMat2::IDENTITY, 1e-9) {
+                continue;
+            }
+
+            // The translation parts `offset + n` worth checking are close to `target`
+            let (target, reach) = if reflected {
+                let normal = reflection_normal(&linear);
+                (
+                    2.0 * centre.dot(normal) * normal,
+                    2.0 * radius + 2.0 * scale,
+                )
+            } else {
+                ((DMat2::IDENTITY - linear) * centre, 2.0 * radius)
+            };
+            let k = inverse * (target - offset);
+            let span = dvec2(
+                inverse.row(0).length() * (reach + eps),
+                inverse.row(1).length() * (reach + eps),
+            );
+            for t1 in (k.x - span.x).floor() as isize..=(k.x + span.x).ceil() as isize {
+                for t2 in (k.y - span.y).floor() as isize..=(k.y + span.y).ceil() as isize {
+                    let b = offset + lattice * dvec2(t1 as f64, t2 as f64);
+                    if reflected {
+                        let normal = reflection_normal(&linear);
+                        let direction = normal.perp();
+                        let (normal, shift) = canonical(normal, b.dot(normal) / 2.0);
+                        let glide = b.dot(direction).abs();
+                        if (shift - centre.dot(normal)).abs() > radius + eps {
+                            continue;
+                        }
+                        match axes.iter_mut().find(|(n, s, _)| {
+                            n.abs_diff_eq(normal, 1e-9) && (s - shift).abs() < eps
+                        }) {
+                            Some(found) => found.2 = found.2.min(glide),
+                            None => axes.push((normal, shift, glide)),
+                        }
+                    } else {
+                        let point = (DMat2::IDENTITY - linear).inverse() * b;
+                        if !region.expand(eps).contains(point) {
+                            continue;
+                        }
+                        let angle = linear.x_axis.y.atan2(linear.x_axis.x).abs();
+                        let order = (TAU / angle).round() as usize;
+                        match elements
+                            .rotations
+                            .iter_mut()
+                            .find(|r| r.centre.distance(point) < eps)
+                        {
+                            Some(found) => found.order = found.order.max(order),
+                            None => elements.rotations.push(RotationCentre {
+                                centre: point,
+                                order,
+                            }),
+                        }
+                    }
+                }
+            }
+        }
+
+        for (normal, shift, glide) in axes {
+            let direction = normal.perp();
+            let axis = Axis {
+                point: normal * shift + direction * centre.dot(direction),
+                direction,
+            };
+            if glide < eps {
+                elements.mirrors.push(axis);
+            } else {
+                elements.glides.push(GlideAxis { axis, glide });
+            }
+        }
+
+        elements
+    }
+}
+
+impl IsohedralTiling {
+    /// Returns the symmetries of the tiling up to translations by the lattice.
+    ///
+    /// These are the transforms mapping the first aspect onto each of the other ones, combined
+    /// with the symmetries of the first tile itself.
+    fn point_symmetries(&self) -> Vec<DMat3> {
+        let first = *self.aspect_transform(0);
+        let stabiliser = self.tile_symmetries();
+        (0..self.num_aspects())
+            .map(|a| *self.aspect_transform(a) * first.inverse())
+            .flat_map(|a| stabiliser.iter().map(move |s| a * *s))
+            .collect()
+    }
+
+    /// Returns the symmetries of the first tile (with lattice coordinates `(0, 0)` and aspect
+    /// `0`) required by the tiling type, starting with the identity.
+    ///
+    /// Tiling types where tiles are symmetric only list some of their symmetries as aspects, so
+    /// the symmetries of the tile are found by composing aspect transforms until the result maps
+    /// the tile onto itself.
+    pub(crate) fn tile_symmetries(&self) -> Vec<DMat3> {
+        let first = *self.aspect_transform(0);
+        let aspects: Vec<_> = (0..self.num_aspects())
+            .map(|a| *self.aspect_transform(a) * first.inverse())
+            .collect();
+        let verts = self.vertices();
+        let bounds = BoundingBox::from_points(verts);
+        let eps = 1e-6 * bounds.width().max(bounds.height());
+        let place =
+            |m: &DMat3| -> Vec<DVec2> { verts.iter().map(|v| m.transform_point2(*v)).collect() };
+
+        let mut stabiliser = vec![DMat3::IDENTITY];
+        let mut grown = true;
+        while grown {
+            grown = false;
+            let symmetries: Vec<_> = aspects
+                .iter()
+                .flat_map(|a| stabiliser.iter().map(move |s| *a * *s))
+                .collect();
+            for a in &symmetries {
+                for b in &symmetries {
+                    let product = *a * *b;
+                    let image = place(&(product * first));
+                    let region = BoundingBox::from_points(&image).expand(eps);
+                    // The tile the product lands on, which may have its vertices in another order
+                    let tile = self
+                        .tiles_in_bounds(&region, &bounds)
+                        .into_iter()
+                        .map(|(t1, t2, aspect)| self.tile_transform(t1, t2, aspect))
+                        .find(|m| {
+                            place(m)
+                                .iter()
+                                .all(|p| image.iter().any(|q| q.distance(*p) < eps))
+                        })
+                        .expect("Symmetries map tiles onto tiles");
+                    let own = first * tile.inverse() * product;
+                    if !stabiliser.iter().any(|s| s.abs_diff_eq(own, 1e-6)) {
+                        stabiliser.push(own);
+                        grown = true;
+                    }
+                }
+            }
+        }
+
+        stabiliser
+    }
+}
+
+/// Returns a unit vector perpendicular to the axis of a reflection through the origin.
+fn reflection_normal(linear: &DMat2) -> DVec2 {
+    let half = linear.x_axis.y.atan2(linear.x_axis.x) / 2.0;
+    dvec2(-half.sin(), half.cos())
+}
+
+/// Picks one of the two opposite normals of a line, so that each line has a single
+/// representation `{ p | p . normal = shift }`.
+fn canonical(normal: DVec2, shift: f64) -> (DVec2, f64) {
+    if normal.y < -1e-9 || (normal.y.abs() <= 1e-9 && normal.x < 0.0) {
+        (-normal, -shift)
+    } else {
+        (normal, shift)
+    }
+}
+
+/// Returns the part of a line inside `region`, if any.
+fn clip_line(axis: &Axis, region: &BoundingBox) -> Option<(DVec2, DVec2)> {
+    let (mut from, mut to) = (f64::NEG_INFINITY, f64::INFINITY);
+    for (p, d, min, max) in [
+        (axis.point.x, axis.direction.x, region.min.x, region.max.x),
+        (axis.point.y, axis.direction.y, region.min.y, region.max.y),
+    ] {
+        if d.abs() < 1e-12 {
+            if p < min || p > max {
+                return None;
+            }
+        } else {
+            let (a, b) = ((min - p) / d, (max - p) / d);
+            from = from.max(a.min(b));
+            to = to.min(a.max(b));
+        }
+    }
+
+    (from < to).then(|| {
+        (
+            axis.point + axis.direction * from,
+            axis.point + axis.direction * to,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use glam::{dvec2, DMat3, DVec2};
+
+    use crate::{
+        data::TILING_TYPES, geometry::BoundingBox, svg::SvgOptions, IsohedralTiling, TilingType,
+    };
+
+    #[test]
+    fn elements_are_symmetries() {
+        let region = BoundingBox {
+            min: dvec2(-2.0, -1.5),
+            max: dvec2(2.0, 1.5),
+        };
+        for &t in TILING_TYPES.iter() {
+            let tiling = IsohedralTiling::new(TilingType(t));
+            let elements = tiling.symmetry_elements(&region);
+            // Each element must map the first tile onto a tile of the tiling, possibly with its
+            // vertices in another order
+            let place = |m: DMat3| -> Vec<DVec2> {
+                tiling
+                    .vertices()
+                    .iter()
+                    .map(|v| m.transform_point2(*v))
+                    .collect()
+            };
+            let around = region.expand(4.0);
+            let tiles: Vec<_> = tiling
+                .tiles_in_bounds(&around, &around)
+                .into_iter()
+                .map(|(t1, t2, a)| place(tiling.tile_transform(t1, t2, a)))
+                .collect();
+            let maps_tiles = |m: DMat3| {
+                let image = place(m * *tiling.aspect_transform(0));
+                tiles.iter().any(|tile| {
+                    tile.iter()
+                        .all(|p| image.iter().any(|q| q.distance(*p) < 1e-6))
+                })
+            };
+            for r in &elements.rotations {
+                assert!([2, 3, 4, 6].contains(&r.order), "IH{}", t);
+                assert!(region.expand(1e-6).contains(r.centre));
+                let angle = std::f64::consts::TAU / r.order as f64;
+                let m = DMat3::from_translation(r.centre)
+                    * DMat3::from_angle(angle)
+                    * DMat3::from_translation(-r.centre);
+                assert!(maps_tiles(m), "IH{}: {:?}", t, r);
+            }
+            let reflection = |point: DVec2, d: DVec2, glide: f64| {
+                let flip = DMat3::from_cols_array(&[
+                    d.x * d.x - d.y * d.y,
+                    2.0 * d.x * d.y,
+                    0.0,
+                    2.0 * d.x * d.y,
+                    d.y * d.y - d.x * d.x,
+                    0.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                ]);
+                DMat3::from_translation(point + d * glide) * flip * DMat3::from_translation(-point)
+            };
+            for m in &elements.mirrors {
+                assert!(maps_tiles(reflection(m.point, m.direction, 0.0)), "IH{}", t);
+            }
+            for g in &elements.glides {
+                let glide = reflection(g.axis.point, g.axis.direction, g.glide);
+                let other = reflection(g.axis.point, g.axis.direction, -g.glide);
+                assert!(maps_tiles(glide) || maps_tiles(other), "IH{}", t);
+            }
+
+            let svg = elements.to_svg(&region, &SvgOptions::default());
+            assert_eq!(svg.matches("<path").count(), elements.rotations.len());
+        }
+
+        // A tiling by translated copies of a single aspect has no other symmetries
+        let elements = IsohedralTiling::new(TilingType(41)).symmetry_elements(&region);
+        assert_eq!(elements, Default::default());
+        // IH61 only has two aspects, but its tiles have a symmetry of order 2, giving p4
+        let elements = IsohedralTiling::new(TilingType(61)).symmetry_elements(&region);
+        let orders: HashSet<_> = elements.rotations.iter().map(|r| r.order).collect();
+        assert_eq!(orders, HashSet::from([2, 4]));
+    }
+}
@@ -0,0 +1,15 @@
+//! Fixtures shared by the tests of several modules.
+use glam::{dvec2, DVec2};
+
+use crate::{EdgeShape, IsohedralTiling};
+
+/// Edge curves for `tiling` going through the control point `bump`, except for the curves of `I`
+/// edges, which must be symmetric and stay straight.
+pub(crate) fn bumpy_edges(tiling: &IsohedralTiling, bump: DVec2) -> Vec<Vec<DVec2>> {
+    (0..tiling.num_edge_shapes())
+        .map(|id| match tiling.edge_shape(id) {
+            EdgeShape::I => vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)],
+            _ => vec![dvec2(0.0, 0.0), bump, dvec2(1.0, 0.0)],
+        })
+        .collect()
+}
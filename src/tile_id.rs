@@ -0,0 +1,156 @@
+//! Compact and stable identifiers for tiles, and per-tile random seeds.
+//!
+//! See [`TileId`].
+use std::fmt;
+
+use crate::{adjacency::TileCoord, utils::splitmix64};
+
+/// The number of bits used to store each lattice coordinate.
+const COORD_BITS: u32 = 30;
+/// The number of bits used to store the aspect.
+const ASPECT_BITS: u32 = 4;
+
+/// A 64-bit identifier for a tile, computed from its lattice coordinates and aspect.
+///
+/// The encoding is fixed, so identifiers are identical across runs and platforms and can be
+/// stored. The aspect goes into the lowest 4 bits, and the two lattice coordinates are mapped to
+/// unsigned integers by zigzag encoding (`0, -1, 1, -2, ...` become `0, 1, 2, 3, ...`) and then
+/// interleaved bit by bit into the upper 60 bits, so that nearby tiles have close identifiers.
+///
+/// Lattice coordinates must be in the range `-2^29..2^29`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TileId(u64);
+
+impl TileId {
+    /// Computes the identifier of a tile.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lattice coordinates are out of range, or if the aspect is
+    /// larger than 15.
+    pub fn new(tile: TileCoord) -> Self {
+        let limit = 1 << (COORD_BITS - 1);
+        assert!(
+            (-limit..limit).contains(&(tile.t1 as i64))
+                && (-limit..limit).contains(&(tile.t2 as i64)),
+            "Lattice coordinates out of range"
+        );
+        assert!(tile.aspect < 1 << ASPECT_BITS, "Aspect out of range");
+
+        let morton = spread(zigzag(tile.t1 as i64)) | spread(zigzag(tile.t2 as i64)) << 1;
+        Self(morton << ASPECT_BITS | tile.aspect as u64)
+    }
+
+    /// Creates an identifier from its integer value, as returned by [`TileId::value`].
+    pub fn from_value(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// The integer value of the identifier.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Decodes the lattice coordinates and aspect of the tile.
+    pub fn coord(self) -> TileCoord {
+        let morton = self.0 >> ASPECT_BITS;
+        TileCoord::new(
+            unzigzag(compact(morton)) as isize,
+            unzigzag(compact(morton >> 1)) as isize,
+            (self.0 & ((1 << ASPECT_BITS) - 1)) as usize,
+        )
+    }
+
+    /// Returns a pseudo-random 64-bit seed for this tile, derived from the identifier and a
+    /// user-provided seed. Different tiles, or different user seeds, give unrelated values.
+    pub fn seed(self, seed: u64) -> u64 {
+        splitmix64(splitmix64(seed) ^ self.0)
+    }
+
+    /// Returns a pseudo-random number in `[0, 1)` for this tile, derived from [`TileId::seed`].
+    pub fn random(self, seed: u64) -> f64 {
+        // Keep the 53 bits that fit exactly in the mantissa
+        (self.seed(seed) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl From<TileCoord> for TileId {
+    fn from(tile: TileCoord) -> Self {
+        Self::new(tile)
+    }
+}
+
+impl From<TileId> for TileCoord {
+    fn from(id: TileId) -> Self {
+        id.coord()
+    }
+}
+
+impl fmt::Display for TileId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+fn zigzag(x: i64) -> u64 {
+    ((x << 1) ^ (x >> 63)) as u64
+}
+
+fn unzigzag(x: u64) -> i64 {
+    (x >> 1) as i64 ^ -((x & 1) as i64)
+}
+
+/// Inserts a zero bit after each of the lowest 32 bits of `x`.
+fn spread(x: u64) -> u64 {
+    let mut x = x & 0xffff_ffff;
+    x = (x | x << 16) & 0x0000_ffff_0000_ffff;
+    x = (x | x << 8) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x << 2) & 0x3333_3333_3333_3333;
+    (x | x << 1) & 0x5555_5555_5555_5555
+}
+
+/// Inverse of [`spread`]: keeps every other bit of `x`, starting from the lowest one.
+fn compact(x: u64) -> u64 {
+    let mut x = x & 0x5555_5555_5555_5555;
+    x = (x | x >> 1) & 0x3333_3333_3333_3333;
+    x = (x | x >> 2) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x >> 4) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x >> 8) & 0x0000_ffff_0000_ffff;
+    (x | x >> 16) & 0xffff_ffff
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::adjacency::TileCoord;
+
+    use super::TileId;
+
+    #[test]
+    fn ids_round_trip_and_are_stable() {
+        let mut ids = HashSet::new();
+        for t1 in -20..20 {
+            for t2 in -20..20 {
+                for aspect in 0..12 {
+                    let tile = TileCoord::new(t1, t2, aspect);
+                    let id = TileId::new(tile);
+                    assert_eq!(id.coord(), tile);
+                    assert!(ids.insert(id));
+                }
+            }
+        }
+        let far = TileCoord::new(-(1 << 29), (1 << 29) - 1, 15);
+        assert_eq!(TileId::new(far).coord(), far);
+
+        // The encoding and seeds must never change
+        assert_eq!(TileId::new(TileCoord::new(0, 0, 0)).value(), 0);
+        assert_eq!(TileId::new(TileCoord::new(1, -1, 3)).value(), 0b0110_0011);
+        let id = TileId::new(TileCoord::new(3, 5, 1));
+        assert_eq!(id.value(), 2497);
+        assert_eq!(id.seed(42), 0x7e54_2e17_565f_3a11);
+        assert_ne!(id.seed(42), id.seed(43));
+        assert!((0.0..1.0).contains(&id.random(7)));
+    }
+}
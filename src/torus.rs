@@ -0,0 +1,144 @@
+//! Finite worlds without borders, made by wrapping a tiling around a torus.
+//!
+//! See [`Torus`].
+use std::collections::HashMap;
+
+use crate::{
+    adjacency::{Adjacency, Neighbourhood, TileCoord},
+    supercell::Supercell,
+    IsohedralTiling,
+};
+
+/// A tiling wrapped around a torus: tiles whose lattice coordinates differ by a translation of a
+/// [`Supercell`] are the same tile, so the world is finite but has no border.
+///
+/// All the methods accept any tile of the plane and wrap it first, so callers can keep moving
+/// around without worrying about the wrapping. The tiles returned are always the representatives
+/// listed by [`Torus::tiles`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Torus {
+    supercell: Supercell,
+    adjacency: Adjacency,
+    tiles: Vec<TileCoord>,
+    index: HashMap<TileCoord, usize>,
+}
+
+impl Torus {
+    /// Wraps `tiling` around the torus given by `supercell`.
+    pub fn new(tiling: &IsohedralTiling, supercell: Supercell) -> Self {
+        let tiles = supercell.tiles(tiling);
+        Self {
+            supercell,
+            adjacency: tiling.adjacency(),
+            index: tiles.iter().enumerate().map(|(i, t)| (*t, i)).collect(),
+            tiles,
+        }
+    }
+
+    /// The supercell the tiling wraps around.
+    pub fn supercell(&self) -> Supercell {
+        self.supercell
+    }
+
+    /// The number of distinct tiles on the torus.
+    pub fn num_tiles(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// The distinct tiles on the torus, in the order of their indices.
+    pub fn tiles(&self) -> &[TileCoord] {
+        &self.tiles
+    }
+
+    /// Returns the representative of a tile on the torus.
+    pub fn wrap(&self, tile: TileCoord) -> TileCoord {
+        let (t1, t2) = self.supercell.wrap(tile.t1, tile.t2);
+        TileCoord::new(t1, t2, tile.aspect)
+    }
+
+    /// Returns the index of a tile in [`Torus::tiles`], after wrapping it.
+    pub fn index(&self, tile: TileCoord) -> usize {
+        self.index[&self.wrap(tile)]
+    }
+
+    /// Whether two tiles of the plane are the same tile on the torus.
+    pub fn same_tile(&self, a: TileCoord, b: TileCoord) -> bool {
+        self.wrap(a) == self.wrap(b)
+    }
+
+    /// Returns the tile on the other side of the given edge of `tile`, along with the index of the
+    /// same edge in that tile. See [`Adjacency::across`].
+    pub fn across(&self, tile: TileCoord, edge: usize) -> (TileCoord, usize) {
+        let (other, back) = self.adjacency.across(tile, edge);
+        (self.wrap(other), back)
+    }
+
+    /// Iterates over the neighbours of `tile`, in the order of its edges. See
+    /// [`Adjacency::neighbours`].
+    pub fn neighbours(&self, tile: TileCoord) -> impl Iterator<Item = TileCoord> + '_ {
+        self.adjacency.neighbours(tile).map(move |t| self.wrap(t))
+    }
+
+    /// Returns the distinct neighbours of `tile` of the given kind.
+    ///
+    /// On a small torus, a tile can be adjacent to another one in several ways, or even to
+    /// itself: each neighbour is returned once, and the tile itself is never returned.
+    pub fn neighbourhood(&self, tile: TileCoord, neighbourhood: Neighbourhood) -> Vec<TileCoord> {
+        let tile = self.wrap(tile);
+        let mut tiles = vec![];
+        for other in self.adjacency.neighbourhood(tile, neighbourhood) {
+            let other = self.wrap(other);
+            if other != tile && !tiles.contains(&other) {
+                tiles.push(other);
+            }
+        }
+
+        tiles
+    }
+}
+
+impl IsohedralTiling {
+    /// Wraps the tiling around the torus given by `supercell`. See [`Torus`].
+    pub fn torus(&self, supercell: Supercell) -> Torus {
+        Torus::new(self, supercell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        adjacency::{Neighbourhood, TileCoord},
+        get_tiling_type,
+        supercell::Supercell,
+        IsohedralTiling, TilingType,
+    };
+
+    #[test]
+    fn neighbours_wrap_around() {
+        let squares = IsohedralTiling::new(TilingType(41));
+        let torus = squares.torus(Supercell::diagonal(3, 3));
+        assert_eq!(torus.num_tiles(), 9);
+        let corner = TileCoord::new(2, 2, 0);
+        assert!(torus.same_tile(TileCoord::new(-1, 5, 0), corner));
+        let edge = torus.neighbourhood(corner, Neighbourhood::Edge);
+        assert_eq!(edge.len(), 4);
+        assert!(edge.contains(&TileCoord::new(0, 2, 0)));
+        assert_eq!(torus.neighbourhood(corner, Neighbourhood::Vertex).len(), 8);
+        // On a 2x1 torus, only the other tile is left
+        let tiny = squares.torus(Supercell::diagonal(2, 1));
+        assert_eq!(tiny.neighbourhood(corner, Neighbourhood::Vertex).len(), 1);
+
+        let tiling = IsohedralTiling::new(get_tiling_type(6));
+        let torus = tiling.torus(Supercell::new([[2, 1], [-1, 2]]).unwrap());
+        assert_eq!(torus.num_tiles(), 5 * tiling.num_aspects());
+        for (idx, &tile) in torus.tiles().iter().enumerate() {
+            assert_eq!(torus.index(tile), idx);
+            for edge in 0..tiling.num_vertices() {
+                let (other, back) = torus.across(tile, edge);
+                assert!(torus.tiles().contains(&other));
+                assert_eq!(torus.across(other, back), (tile, edge));
+            }
+            assert!(torus.neighbours(tile).all(|t| torus.wrap(t) == t));
+        }
+    }
+}
@@ -53,3 +53,48 @@ pub(crate) static TSPI_S: [DMat3; 2] = [
     const_dmat3!([0.5, 0.0, 0.0], [0.0, 0.5, 0.0], [0.0, 0.0, 1.0]),
     const_dmat3!([-0.5, 0.0, 0.0], [0.0, -0.5, 0.0], [1.0, 0.0, 1.0]),
 ];
+
+/// Solves the square linear system `a * x = b` using Gaussian elimination with partial pivoting,
+/// where `a` is an `n * n` matrix stored row by row. Returns `None` if the matrix is singular.
+pub(crate) fn solve_linear(mut a: Vec<f64>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))
+            .unwrap();
+        if a[pivot * n + col].abs() < 1e-300 {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap(pivot * n + k, col * n + k);
+            }
+            b.swap(pivot, col);
+        }
+        for row in (col + 1)..n {
+            let f = a[row * n + col] / a[col * n + col];
+            for k in col..n {
+                a[row * n + k] -= f * a[col * n + k];
+            }
+            b[row] -= f * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let mut total = b[row];
+        for k in (row + 1)..n {
+            total -= a[row * n + k] * x[k];
+        }
+        x[row] = total / a[row * n + row];
+    }
+    Some(x)
+}
+
+/// The finalizer of the SplitMix64 generator, which scrambles the bits of its input.
+pub(crate) fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,292 @@
+//! Consistency checks for a tiling configuration.
+//!
+//! See [`IsohedralTiling::verify`].
+use std::fmt;
+
+use glam::{dvec2, DMat3, DVec2};
+
+use crate::{
+    geometry::{self, BoundingBox},
+    EdgeShape, IsohedralTiling,
+};
+
+/// The different problems that [`IsohedralTiling::verify`] can detect.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyError {
+    /// No curve was given for this edge shape.
+    MissingEdgeCurve {
+        /// The id of the edge shape.
+        shape_id: usize,
+    },
+    /// The curve for this edge shape doesn't go from `(0, 0)` to `(1, 0)`.
+    InvalidEdgeCurve {
+        /// The id of the edge shape.
+        shape_id: usize,
+    },
+    /// The curve for this edge shape doesn't have the symmetries required by its [`EdgeShape`].
+    EdgeShapeViolation {
+        /// The id of the edge shape.
+        shape_id: usize,
+        /// The shape the curve should have.
+        shape: EdgeShape,
+    },
+    /// The outline of the prototile touches or crosses itself.
+    SelfIntersection,
+    /// An edge of a tile isn't matched exactly by an edge of exactly one neighbouring tile.
+    UnmatchedEdge {
+        /// The aspect of the tile.
+        aspect: usize,
+        /// The index of the edge in the prototile.
+        edge: usize,
+    },
+    /// Two tiles overlap.
+    Overlap {
+        /// The aspect of the first tile, which is located in the unit cell at the origin.
+        aspect: usize,
+        /// The lattice coordinates and aspect of the second tile.
+        other: (isize, isize, usize),
+    },
+    /// The area of the tiles doesn't add up to the area of the translational unit cell, meaning
+    /// that the tiles either leave gaps or overlap.
+    AreaMismatch {
+        /// The total area of one copy of each aspect.
+        tiles: f64,
+        /// The area of the translational unit cell.
+        unit_cell: f64,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::MissingEdgeCurve { shape_id } => {
+                write!(f, "no curve given for edge shape {}", shape_id)
+            }
+            VerifyError::InvalidEdgeCurve { shape_id } => write!(
+                f,
+                "the curve for edge shape {} doesn't go from (0, 0) to (1, 0)",
+                shape_id
+            ),
+            VerifyError::EdgeShapeViolation { shape_id, shape } => write!(
+                f,
+                "the curve for edge shape {} doesn't have the symmetries of a {:?} edge",
+                shape_id, shape
+            ),
+            VerifyError::SelfIntersection => {
+                f.write_str("the outline of the prototile isn't simple")
+            }
+            VerifyError::UnmatchedEdge { aspect, edge } => write!(
+                f,
+                "edge {} of aspect {} isn't matched by exactly one neighbouring edge",
+                edge, aspect
+            ),
+            VerifyError::Overlap { aspect, other } => write!(
+                f,
+                "the tile with aspect {} overlaps the tile {:?}",
+                aspect, other
+            ),
+            VerifyError::AreaMismatch { tiles, unit_cell } => write!(
+                f,
+                "the tiles cover an area of {} but the unit cell has an area of {}",
+                tiles, unit_cell
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl IsohedralTiling {
+    /// Checks that the current tiling, drawn with the given edge curves, is consistent.
+    ///
+    /// This verifies that:
+    /// - there is a valid curve for each edge shape, and that curves of `I` edges have the
+    ///   required symmetries (the symmetries of `U` and `S` edges are guaranteed by construction,
+    ///   see [`outline`]),
+    /// - the outline of the prototile is a simple polygon,
+    /// - every edge of every aspect is matched exactly by an edge of one neighbouring tile,
+    /// - the tiles surrounding the unit cell at the origin don't overlap,
+    /// - the tiles of one unit cell cover exactly the area of the unit cell, so there are no
+    ///   gaps.
+    ///
+    /// The first problem found is returned as an error.
+    ///
+    /// [`outline`]: IsohedralTiling::outline
+    pub fn verify(&self, edges: &[Vec<DVec2>]) -> Result<(), VerifyError> {
+        self.verify_edge_curves(edges)?;
+
+        let outline = self.outline(edges);
+        let paths = self.edge_paths(edges);
+        let bounds = BoundingBox::from_points(&outline);
+        let eps = 1e-7 * bounds.width().max(bounds.height()).max(1.0);
+
+        if !geometry::is_simple(&outline) {
+            return Err(VerifyError::SelfIntersection);
+        }
+
+        // Check that the tiles of a unit cell exactly cover it
+        let tiles_area = geometry::area(&outline) * self.num_aspects() as f64;
+        let unit_cell = self.unit_cell_area();
+        if unit_cell < eps || (tiles_area - unit_cell).abs() > eps * unit_cell.max(1.0) {
+            return Err(VerifyError::AreaMismatch {
+                tiles: tiles_area,
+                unit_cell,
+            });
+        }
+
+        for aspect in 0..self.num_aspects() {
+            let m = self.tile_transform(0, 0, aspect);
+            let tile = transform_points(&m, &outline);
+            let tile_bounds = bounds.transform(&m).expand(eps);
+
+            let neighbours: Vec<_> = self
+                .tiles_in_bounds(&tile_bounds, &bounds)
+                .into_iter()
+                .filter(|&t| t != (0, 0, aspect))
+                .map(|(t1, t2, other)| ((t1, t2, other), self.tile_transform(t1, t2, other)))
+                .collect();
+
+            // Check that each edge has exactly one matching edge in the neighbouring tiles
+            for (edge, path) in paths.iter().enumerate() {
+                let path = transform_points(&m, path);
+                let matches: usize = neighbours
+                    .iter()
+                    .map(|(_, n)| {
+                        paths
+                            .iter()
+                            .filter(|other| same_path(&path, &transform_points(n, other), eps))
+                            .count()
+                    })
+                    .sum();
+                if matches != 1 {
+                    return Err(VerifyError::UnmatchedEdge { aspect, edge });
+                }
+            }
+
+            // Check that no neighbouring tile overlaps this one
+            for (other, n) in &neighbours {
+                if n.abs_diff_eq(m, eps) || overlaps(&tile, &transform_points(n, &outline), eps) {
+                    return Err(VerifyError::Overlap {
+                        aspect,
+                        other: *other,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn verify_edge_curves(&self, edges: &[Vec<DVec2>]) -> Result<(), VerifyError> {
+        for shape_id in 0..self.num_edge_shapes() {
+            let curve = edges
+                .get(shape_id)
+                .ok_or(VerifyError::MissingEdgeCurve { shape_id })?;
+            if curve.len() < 2
+                || !curve[0].abs_diff_eq(DVec2::ZERO, 1e-9)
+                || !curve[curve.len() - 1].abs_diff_eq(dvec2(1.0, 0.0), 1e-9)
+            {
+                return Err(VerifyError::InvalidEdgeCurve { shape_id });
+            }
+
+            let shape = self.edge_shape(shape_id);
+            if shape == EdgeShape::I {
+                // The curve must be invariant under both a reflection across the perpendicular
+                // bisector of the edge and a half-turn around its midpoint.
+                let reflected: Vec<_> = curve.iter().rev().map(|p| dvec2(1.0 - p.x, p.y)).collect();
+                let rotated: Vec<_> = curve.iter().rev().map(|p| dvec2(1.0 - p.x, -p.y)).collect();
+                if !same_points(curve, &reflected, 1e-9) || !same_points(curve, &rotated, 1e-9) {
+                    return Err(VerifyError::EdgeShapeViolation { shape_id, shape });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn transform_points(m: &DMat3, points: &[DVec2]) -> Vec<DVec2> {
+    points.iter().map(|p| m.transform_point2(*p)).collect()
+}
+
+fn same_points(a: &[DVec2], b: &[DVec2], eps: f64) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(p, q)| p.abs_diff_eq(*q, eps))
+}
+
+/// Whether the two paths are the same, in either direction.
+fn same_path(a: &[DVec2], b: &[DVec2], eps: f64) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    same_points(a, b, eps)
+        || a.iter()
+            .zip(b.iter().rev())
+            .all(|(p, q)| p.abs_diff_eq(*q, eps))
+}
+
+/// Whether the interiors of the two polygons intersect.
+fn overlaps(a: &[DVec2], b: &[DVec2], eps: f64) -> bool {
+    let na = a.len();
+    let nb = b.len();
+    for i in 0..na {
+        for j in 0..nb {
+            if geometry::segments_cross(a[i], a[(i + 1) % na], b[j], b[(j + 1) % nb], eps) {
+                return true;
+            }
+        }
+    }
+
+    // No crossing edges: one polygon could still be inside the other
+    let strictly_inside = |poly: &[DVec2], p: DVec2| {
+        geometry::contains_point(poly, p) && geometry::distance_to_boundary(poly, p) > eps
+    };
+    let midpoints = |poly: &[DVec2]| {
+        let n = poly.len();
+        (0..n)
+            .map(|idx| (poly[idx] + poly[(idx + 1) % n]) * 0.5)
+            .collect::<Vec<_>>()
+    };
+    b.iter()
+        .chain(midpoints(b).iter())
+        .any(|p| strictly_inside(a, *p))
+        || a.iter()
+            .chain(midpoints(a).iter())
+            .any(|p| strictly_inside(b, *p))
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use crate::{get_tiling_type, test_utils, IsohedralTiling};
+
+    use super::VerifyError;
+
+    #[test]
+    fn all_tiling_types_are_consistent() {
+        for n in 0..81 {
+            let tiling = IsohedralTiling::new(get_tiling_type(n));
+            let edges = test_utils::bumpy_edges(&tiling, dvec2(0.4, 0.1));
+            assert_eq!(tiling.verify(&edges), Ok(()), "{}", tiling.tiling_type());
+        }
+    }
+
+    #[test]
+    fn detects_invalid_configurations() {
+        let mut tiling = IsohedralTiling::new(get_tiling_type(0));
+        let edges = vec![vec![dvec2(0.0, 0.0), dvec2(1.0, 0.0)]; 3];
+        assert_eq!(
+            tiling.verify(&edges[..2]),
+            Err(VerifyError::MissingEdgeCurve { shape_id: 2 })
+        );
+
+        // An edge curve folding back onto itself
+        let mut folded = edges.clone();
+        folded[0] = vec![dvec2(0.0, 0.0), dvec2(2.0, 0.2), dvec2(1.0, 0.0)];
+        assert_eq!(tiling.verify(&folded), Err(VerifyError::SelfIntersection));
+
+        // Collapse the prototile
+        tiling.set_parameters(&[0.0; 6]);
+        assert!(tiling.verify(&edges).is_err());
+    }
+}
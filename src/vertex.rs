@@ -0,0 +1,173 @@
+//! Which tiles meet at each vertex of a tiling.
+//!
+//! See [`IsohedralTiling::vertex_stars`].
+use std::f64::consts::TAU;
+
+use glam::DVec2;
+
+use crate::{
+    adjacency::TileCoord,
+    geometry::{self, BoundingBox},
+    IsohedralTiling,
+};
+
+/// A tile meeting at a vertex of the tiling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corner {
+    /// The position of the tile, relative to the tile the vertex belongs to: the lattice
+    /// coordinates are offsets, to be added to the ones of that tile.
+    pub tile: TileCoord,
+    /// The index of the prototile vertex of `tile` lying on the tiling vertex.
+    pub vertex: usize,
+    /// The angle of the tile at the vertex, in radians.
+    pub angle: f64,
+}
+
+/// The tiles meeting at a vertex of the tiling, also known as its vertex star.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexStar {
+    /// The index of the vertex in the prototile.
+    pub vertex: usize,
+    /// The position of the vertex, for the tile with lattice coordinates `(0, 0)` and the given
+    /// aspect.
+    pub position: DVec2,
+    /// The tiles meeting at the vertex, in counter-clockwise order around it, starting with the
+    /// tile the vertex belongs to. The angles of the corners add up to a full turn.
+    pub corners: Vec<Corner>,
+}
+
+impl VertexStar {
+    /// The number of tiles meeting at the vertex.
+    pub fn valence(&self) -> usize {
+        self.corners.len()
+    }
+}
+
+impl IsohedralTiling {
+    /// Computes the vertex star of each vertex of the tile with the given aspect, in the order of
+    /// the prototile vertices.
+    ///
+    /// Like [`IsohedralTiling::adjacency`], the tiles meeting at each vertex only depend on the
+    /// tiling type, while the positions and angles use the current parameters.
+    pub fn vertex_stars(&self, aspect: usize) -> Vec<VertexStar> {
+        let reference = IsohedralTiling::new(self.tiling_type());
+        let verts = reference.vertices();
+        let n = verts.len();
+        let bounds = BoundingBox::from_points(verts);
+        let eps = 1e-6 * bounds.width().max(bounds.height());
+        let m = reference.aspect_transform(aspect);
+
+        (0..n)
+            .map(|vertex| {
+                let p = m.transform_point2(verts[vertex]);
+                let region = BoundingBox::from_points(&[p]).expand(eps);
+                let mut corners: Vec<_> = reference
+                    .tiles_in_bounds(&region, &bounds)
+                    .into_iter()
+                    .filter_map(|(t1, t2, other)| {
+                        let m = reference.tile_transform(t1, t2, other);
+                        (0..n)
+                            .find(|&v| m.transform_point2(verts[v]).distance(p) < eps)
+                            .map(|v| (TileCoord::new(t1, t2, other), v))
+                    })
+                    .map(|(tile, v)| {
+                        let (start, angle) = self.corner(tile, v);
+                        (
+                            start,
+                            Corner {
+                                tile,
+                                vertex: v,
+                                angle,
+                            },
+                        )
+                    })
+                    .collect();
+
+                // Sort by the direction of the first side of each corner, starting from the tile
+                // the vertex belongs to
+                let own = corners
+                    .iter()
+                    .find(|(_, c)| c.tile == TileCoord::new(0, 0, aspect))
+                    .expect("A vertex belongs to its own tile")
+                    .0;
+                corners.sort_by(|(a, _), (b, _)| {
+                    let a = (a - own).rem_euclid(TAU);
+                    let b = (b - own).rem_euclid(TAU);
+                    a.total_cmp(&b)
+                });
+
+                VertexStar {
+                    vertex,
+                    position: self
+                        .aspect_transform(aspect)
+                        .transform_point2(self.vertices()[vertex]),
+                    corners: corners.into_iter().map(|(_, c)| c).collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the direction of the side starting the corner of `tile` at its vertex `vertex`,
+    /// going counter-clockwise, and the angle of the corner.
+    fn corner(&self, tile: TileCoord, vertex: usize) -> (f64, f64) {
+        let m = self.tile_transform(tile.t1, tile.t2, tile.aspect);
+        let poly: Vec<_> = self
+            .vertices()
+            .iter()
+            .map(|v| m.transform_point2(*v))
+            .collect();
+        let n = poly.len();
+        let p = poly[vertex];
+        let mut next = poly[(vertex + 1) % n] - p;
+        let mut prev = poly[(vertex + n - 1) % n] - p;
+        // The inside of the tile is on the left of its edges if it is counter-clockwise
+        if geometry::signed_area(&poly) < 0.0 {
+            std::mem::swap(&mut next, &mut prev);
+        }
+        let start = next.y.atan2(next.x);
+        let angle = (prev.y.atan2(prev.x) - start).rem_euclid(TAU);
+        (start, angle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, TAU};
+
+    use crate::{adjacency::TileCoord, data::TILING_TYPES, IsohedralTiling, TilingType};
+
+    #[test]
+    fn corners_make_full_turns() {
+        for &t in TILING_TYPES.iter() {
+            let tiling = IsohedralTiling::new(TilingType(t));
+            for aspect in 0..tiling.num_aspects() {
+                let stars = tiling.vertex_stars(aspect);
+                assert_eq!(stars.len(), tiling.num_vertices());
+                for star in &stars {
+                    let total: f64 = star.corners.iter().map(|c| c.angle).sum();
+                    assert!((total - TAU).abs() < 1e-9, "IH{}: {}", t, total);
+                    assert!(star.valence() >= 3, "IH{}", t);
+                    let own = star.corners[0];
+                    assert_eq!(
+                        (own.tile, own.vertex),
+                        (TileCoord::new(0, 0, aspect), star.vertex)
+                    );
+                    // Each tile sees the same vertex star
+                    for corner in &star.corners {
+                        let other = &tiling.vertex_stars(corner.tile.aspect)[corner.vertex];
+                        assert_eq!(other.valence(), star.valence(), "IH{}", t);
+                    }
+                }
+            }
+        }
+
+        let squares = IsohedralTiling::new(TilingType(41));
+        for star in squares.vertex_stars(0) {
+            assert_eq!(star.valence(), 4);
+            assert!(star
+                .corners
+                .iter()
+                .all(|c| (c.angle - FRAC_PI_2).abs() < 1e-9));
+        }
+    }
+}